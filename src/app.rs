@@ -1,14 +1,208 @@
-use std::usize;
+use std::{
+    cmp,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    future::Future,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
 
-use crate::{dbus::DBusClient, error::DBusConsoleError, filter::filter_bus_names};
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    future::{AbortHandle, Abortable},
+    stream, StreamExt,
+};
 
+use crate::{
+    arguments::{parse_arguments, parse_value},
+    bus::{BusAddress, PEER_NAME},
+    dbus::{
+        add_interfaces, parent_path, remove_interfaces, DBusClient, Discovery, IndexedMember,
+        Interface, MemberKind, Method, NameDetails, NameOwnerChange, ObjectChange, ObjectNode,
+        Property,
+    },
+    error::{DBusConsoleError, DBusConsoleResult},
+    filter::{filter_bus_names, name_rows, next_visible, previous_visible, visible_rows, Search},
+    format::{format_body, format_value},
+    monitor::{start_bus_monitor, MonitoredMessage},
+    signals::{MatchRule, Signal, SignalMonitor},
+};
+
+/// Older signals are dropped once this many were received.
+const MAX_SIGNALS: usize = 1000;
+/// Older messages are dropped once this many were monitored.
+const MAX_MONITORED_MESSAGES: usize = 5000;
+/// Older entries are dropped once this many actions and events were logged.
+const MAX_LOG_ENTRIES: usize = 1000;
+/// How long names that changed are highlighted.
+const NAME_HIGHLIGHT: Duration = Duration::from_secs(3);
+
+static NEXT_TASK_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// One tab per context, each connected to a bus of its own.
 pub struct App {
+    pub contexts: Vec<BusContext>,
+    /// Index of the context shown.
+    pub active: usize,
+    next_context_id: usize,
+    discovery: Discovery,
+    sender: UnboundedSender<(usize, AppEvent)>,
+    receiver: UnboundedReceiver<(usize, AppEvent)>,
+}
+
+/// Everything shown for one bus, kept while another tab is shown.
+pub struct BusContext {
+    /// Identifies the context in events sent from other threads.
+    pub id: usize,
+    pub bus: BusAddress,
+    client: Option<DBusClient>,
+    pub bus_input: String,
+    pub bus_input_error: Option<DBusConsoleError>,
     pub bus_name_state: ListState<String>,
-    pub paths: Vec<String>,
-    pub methods: Vec<String>,
-    pub filter_aliases: bool,
+    /// Names that appeared, vanished or changed their owner within the last `NAME_HIGHLIGHT`,
+    /// vanished ones are only removed from the list then.
+    pub name_changes: HashMap<String, (NameChange, Instant)>,
+    name_watch: Option<AbortHandle>,
+    /// Names a service is started for on demand.
+    pub activatable: HashSet<String>,
+    /// Activatable names listed though nothing owns them, they're only browsed once started.
+    pub inactive: HashSet<String>,
+    /// The owner and its process by name, for the names running.
+    pub name_details: HashMap<String, NameDetails>,
+    pub name_sort: NameSort,
+    /// The visible rows of the object tree.
+    pub paths: ListState<String>,
+    /// Every object introspected so far, by path.
+    pub objects: HashMap<String, ObjectNode>,
+    pub expanded_paths: HashSet<String>,
+    pub discovery: Discovery,
+    /// Objects found by the running discovery so far.
+    pub discovered: usize,
+    /// Object managers whose changes are applied to `objects`.
+    watched_managers: HashSet<String>,
+    object_watches: Vec<AbortHandle>,
+    pub interfaces: Vec<Interface>,
+    /// The visible rows of the interface tree.
+    pub members: ListState<MemberRow>,
+    /// Interfaces expanded or collapsed by hand, see `is_expanded`.
+    pub expanded: HashMap<String, bool>,
+    pub properties: ListState<Property>,
+    pub member_view: MemberView,
+    pub arguments: String,
+    pub call_result: Option<DBusConsoleResult<String>>,
+    pub property_value: String,
+    pub property_result: Option<DBusConsoleResult<String>>,
+    /// Leaves the unique names of connections out of the bus names, unless they're grouped.
+    pub hide_unique_names: bool,
+    /// Lists the well-known names below the connections owning them.
+    pub group_names: bool,
     pub focus: Section,
+    /// The search of each list pane that has one.
+    pub searches: HashMap<Section, Search>,
+    /// The interfaces and members of every service, filled in while the services are crawled.
+    pub member_index: ListState<IndexedMember>,
+    /// The member jumped to, selected once the interfaces of its path are loaded.
+    jump: Option<IndexedMember>,
+    /// The interfaces typed into the object query, separated by spaces.
+    pub object_query: String,
+    /// The interfaces the found objects implement, as last queried.
+    pub queried_interfaces: Vec<String>,
+    /// How many services the last query looks through.
+    pub queried_services: usize,
+    pub found_objects: ListState<FoundObject>,
     pub log: ListState<LogEntry>,
+    pub signals: ListState<Signal>,
+    pub signal_monitor: Option<SignalMonitor>,
+    /// The rule the signal monitor currently uses, `None` until one was added.
+    pub match_rule: Option<MatchRule>,
+    pub match_rule_input: String,
+    pub match_rule_error: Option<DBusConsoleError>,
+    pub traffic_view: TrafficView,
+    pub monitored_messages: ListState<MonitoredMessage>,
+    /// Stops the bus monitor, `None` while it's not running.
+    bus_monitor: Option<AbortHandle>,
+    /// The last failure not shown next to what caused it, until it's dismissed.
+    pub error: Option<DBusConsoleError>,
+    /// Requests running in the background, their results are dropped once they're removed.
+    pub tasks: Vec<Task>,
+    pub events: EventSender,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameChange {
+    Appeared,
+    Vanished,
+    OwnerChanged,
+}
+
+/// The column the bus names are sorted by, names without the detail come last.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameSort {
+    Name,
+    Pid,
+    Uid,
+    Command,
+}
+
+impl NameSort {
+    fn next(self) -> Self {
+        match self {
+            NameSort::Name => NameSort::Pid,
+            NameSort::Pid => NameSort::Uid,
+            NameSort::Uid => NameSort::Command,
+            NameSort::Command => NameSort::Name,
+        }
+    }
+}
+
+/// An object implementing all interfaces of the object query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoundObject {
+    pub service: String,
+    pub path: String,
+    /// The properties of the queried interfaces, once they're compared.
+    pub properties: Option<DBusConsoleResult<Vec<Property>>>,
+}
+
+/// A request to the bus running in the background, see `spawn`.
+#[derive(Clone)]
+pub struct Task {
+    pub id: usize,
+    /// What's loading, a task replaces a running one of the same kind and key.
+    pub kind: &'static str,
+    /// What the task loads for if several of its kind may run at once, e.g. a path.
+    pub key: Option<String>,
+    abort: AbortHandle,
+}
+
+/// Leaves out the abort handle, which would clutter the log.
+impl Debug for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Task")
+            .field("id", &self.id)
+            .field("kind", &self.kind)
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends events produced outside of `reduce`, e.g. by the signal monitor, to the context they
+/// belong to.
+#[derive(Debug, Clone)]
+pub struct EventSender {
+    context: usize,
+    sender: UnboundedSender<(usize, AppEvent)>,
+}
+
+impl EventSender {
+    pub fn new(context: usize, sender: UnboundedSender<(usize, AppEvent)>) -> Self {
+        Self { context, sender }
+    }
+
+    /// Returns `false` once the app is gone.
+    pub fn send(&self, event: AppEvent) -> bool {
+        self.sender.unbounded_send((self.context, event)).is_ok()
+    }
 }
 
 #[derive(Debug)]
@@ -21,7 +215,6 @@ pub struct ListState<T> {
     pub entries: Vec<T>,
     pub selected: Option<u32>,
     pub skip: u32,
-    pub visible_lines: u32,
 }
 
 impl<T> Default for ListState<T> {
@@ -30,57 +223,486 @@ impl<T> Default for ListState<T> {
             entries: Default::default(),
             selected: Default::default(),
             skip: Default::default(),
-            visible_lines: Default::default(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+impl<T> ListState<T> {
+    pub fn selected_entry(&self) -> Option<&T> {
+        self.selected
+            .and_then(|index| self.entries.get(index as usize))
+    }
+
+    /// Selects the next of the `visible` rows, the ones a search hides are skipped.
+    fn select_next(&mut self, visible: &[usize]) -> bool {
+        match next_visible(self.selected, visible) {
+            Some(index) => self.select(index),
+            None => false,
+        }
+    }
+
+    fn select_previous(&mut self, visible: &[usize]) -> bool {
+        match previous_visible(self.selected, visible) {
+            Some(index) => self.select(index),
+            None => false,
+        }
+    }
+
+    fn select(&mut self, index: usize) -> bool {
+        if index < self.entries.len() {
+            self.selected = Some(index as u32);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn replace(&mut self, entries: Vec<T>) {
+        self.entries = entries;
+        self.selected = None;
+        self.skip = 0;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Section {
     BusFrame,
     BusPath,
+    Methods,
+    Arguments,
+    Properties,
+    PropertyValue,
+    Signals,
+    MatchRule,
+    Monitor,
+    BusAddress,
+    /// Finds members of all services in the member index.
+    MemberSearch,
+    /// The interfaces objects are looked for by.
+    ObjectQuery,
+    FoundObjects,
+}
+
+/// Which received messages the bottom right shows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrafficView {
+    Signals,
+    Monitor,
+}
+
+/// A row of the interface tree, the indices point into `BusContext::interfaces` and the
+/// interface's members.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MemberRow {
+    Interface(usize),
+    Method(usize, usize),
+    Signal(usize, usize),
+    Property(usize, usize),
+}
+
+impl MemberRow {
+    pub fn interface(&self) -> usize {
+        match *self {
+            MemberRow::Interface(interface)
+            | MemberRow::Method(interface, _)
+            | MemberRow::Signal(interface, _)
+            | MemberRow::Property(interface, _) => interface,
+        }
+    }
+}
+
+/// Which members of the selected path the right hand side shows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberView {
+    Methods,
+    Properties,
 }
 
 #[derive(Debug, Clone)]
 pub enum Action {
     None,
     Quit,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PreviousTab,
+    /// Actions reduced one after another.
+    Batch(Vec<Action>),
+    Connect {
+        address: String,
+    },
+    FocusBusAddress,
+    InputBusAddress(char),
+    DeleteBusAddress,
     FocusBusNames,
     FocusPaths,
+    FocusMethods,
+    FocusArguments,
+    FocusProperties,
+    FocusPropertyValue,
+    FocusSignals,
+    FocusMatchRule,
+    FocusMonitor,
+    FocusMemberSearch,
+    FocusObjectQuery,
+    FocusFoundObjects,
+    CancelTasks,
+    DismissError,
+    /// Forgets the cached introspection data and loads the selected path again.
+    Refresh,
+    StartBusMonitor,
     LoadBusNames,
-    LoadPaths { bus_name: String },
-    Resize { section: Section, rows: i32 },
+    LoadActivatableNames,
+    /// Looks up the owners of `names` and their processes.
+    LoadNameDetails {
+        names: Vec<String>,
+    },
+    /// Sorts the bus names by the next column.
+    SortBusNames,
+    ToggleNameGroups,
+    ToggleUniqueNames,
+    /// Starts the service activatable as `bus_name`.
+    ActivateBusName {
+        bus_name: String,
+    },
+    /// Lists the bus names and keeps them up to date.
+    WatchBusNames,
+    /// Ends the highlight of the change of `name` made at `at`.
+    ExpireNameChange {
+        name: String,
+        at: Instant,
+    },
+    LoadPaths {
+        bus_name: String,
+    },
+    LoadInterfaces {
+        bus_name: String,
+        path: String,
+    },
+    CallMethod {
+        bus_name: String,
+        path: String,
+        interface: String,
+        method: String,
+        arguments: String,
+    },
+    LoadProperties {
+        bus_name: String,
+        path: String,
+    },
+    GetProperty {
+        bus_name: String,
+        path: String,
+        interface: String,
+        name: String,
+    },
+    SetProperty {
+        bus_name: String,
+        path: String,
+        interface: String,
+        name: String,
+        signature: String,
+        value: String,
+    },
     SelectLastBusName,
     SelectNextBusName,
+    SelectLastPath,
+    SelectNextPath,
+    /// Expands the selected path, `recursive` expands everything below it too.
+    ExpandPath {
+        recursive: bool,
+    },
+    CollapsePath,
+    TogglePath,
+    LoadChildren {
+        bus_name: String,
+        path: String,
+        recursive: bool,
+    },
+    /// Keeps the objects below the object managers at `paths` up to date.
+    WatchObjects {
+        bus_name: String,
+        paths: Vec<String>,
+    },
+    SelectLastMember,
+    SelectNextMember,
+    ExpandInterface,
+    CollapseInterface,
+    ToggleInterface,
+    SelectLastProperty,
+    SelectNextProperty,
+    SelectLastSignal,
+    SelectNextSignal,
+    SelectLastMonitoredMessage,
+    SelectNextMonitoredMessage,
+    /// Crawls all services running for the member index.
+    IndexMembers,
+    SelectLastIndexedMember,
+    SelectNextIndexedMember,
+    /// Shows `member` in the object and interface tree.
+    JumpToMember(IndexedMember),
+    InputObjectQuery(char),
+    DeleteObjectQuery,
+    /// Looks for the objects implementing all of `interfaces` in the bus names listed.
+    FindObjects {
+        interfaces: Vec<String>,
+    },
+    ScanObjects {
+        services: Vec<String>,
+        interfaces: Vec<String>,
+    },
+    SelectLastFoundObject,
+    SelectNextFoundObject,
+    /// Reads the properties of the queried interfaces of all found objects.
+    CompareFoundObjects,
+    InputArgument(char),
+    DeleteArgument,
+    InputPropertyValue(char),
+    DeletePropertyValue,
+    InputMatchRule(char),
+    DeleteMatchRule,
+    ApplyMatchRule {
+        rule: String,
+    },
+    /// Opens the search prompt of the focused pane.
+    Search,
+    InputSearch(char),
+    DeleteSearch,
+    CycleSearchMode,
+    /// Closes the search prompt, the rows stay filtered.
+    FinishSearch,
+    ClearSearch,
 }
 
 #[derive(Debug, Clone)]
 pub enum AppEvent {
     None,
     Error(DBusConsoleError),
+    ErrorDismissed,
+    TaskStarted(Task),
+    /// The result of the task with the id `id`.
+    TaskFinished {
+        id: usize,
+        event: Box<AppEvent>,
+    },
+    TasksCancelled,
+    CacheCleared,
+    Connected {
+        address: BusAddress,
+        client: DBusClient,
+        signal_monitor: SignalMonitor,
+    },
+    ConnectFailed(DBusConsoleError),
+    FocusBusAddress,
+    BusAddressInput(char),
+    BusAddressDeleted,
     BusNamesLoaded(Vec<String>),
-    PathsLoaded(Vec<String>),
-    MethodsLoaded(Vec<String>),
+    ActivatableNamesLoaded(Vec<String>),
+    NameDetailsLoaded {
+        name: String,
+        details: NameDetails,
+    },
+    BusNamesSorted,
+    NameGroupsToggled,
+    UniqueNamesToggled,
+    ServiceStarted(String),
+    BusNamesWatched(AbortHandle),
+    NameOwnerChanged(NameOwnerChange),
+    NameChangeExpired {
+        name: String,
+        at: Instant,
+    },
+    PathsLoaded(Vec<ObjectNode>),
+    ChildrenLoaded {
+        path: String,
+        nodes: Vec<ObjectNode>,
+        recursive: bool,
+    },
+    /// Reported while a subtree is discovered, before the whole of it is loaded.
+    NodesDiscovered {
+        bus_name: String,
+        nodes: Vec<ObjectNode>,
+        recursive: bool,
+    },
+    ObjectsWatched {
+        bus_name: String,
+        watch: AbortHandle,
+    },
+    ObjectsChanged {
+        bus_name: String,
+        change: ObjectChange,
+    },
+    PathExpanded {
+        recursive: bool,
+    },
+    PathCollapsed,
+    InterfacesLoaded(Vec<Interface>),
+    MethodCalled(String),
+    MethodCallFailed(DBusConsoleError),
+    PropertiesLoaded(Vec<Property>),
+    PropertyLoaded {
+        interface: String,
+        name: String,
+        value: String,
+    },
+    PropertySet {
+        bus_name: String,
+        path: String,
+        interface: String,
+        name: String,
+    },
+    PropertySetFailed(DBusConsoleError),
+    SignalReceived(Signal),
+    MatchRuleApplied(MatchRule),
+    MatchRuleFailed(DBusConsoleError),
+    BusMonitorStarted(AbortHandle),
+    MessageMonitored(MonitoredMessage),
     SelectNextBusName,
     SelectPreviousBusName,
+    SelectNextPath,
+    SelectPreviousPath,
+    SelectNextMember,
+    SelectPreviousMember,
+    InterfaceExpanded(bool),
+    SelectNextProperty,
+    SelectPreviousProperty,
+    SelectNextSignal,
+    SelectPreviousSignal,
+    SelectNextMonitoredMessage,
+    SelectPreviousMonitoredMessage,
+    MembersIndexed(Vec<IndexedMember>),
+    SelectNextIndexedMember,
+    SelectPreviousIndexedMember,
+    /// The objects up to the path of `member` are loaded.
+    JumpLoaded {
+        member: IndexedMember,
+        nodes: Vec<ObjectNode>,
+    },
+    ObjectQueryInput(char),
+    ObjectQueryDeleted,
+    ObjectQueryStarted {
+        interfaces: Vec<String>,
+    },
+    ObjectsFound(Vec<FoundObject>),
+    SelectNextFoundObject,
+    SelectPreviousFoundObject,
+    ObjectCompared {
+        service: String,
+        path: String,
+        properties: DBusConsoleResult<Vec<Property>>,
+    },
     FocusBusNames,
     FocusPaths,
+    FocusMethods,
+    FocusArguments,
+    FocusProperties,
+    FocusPropertyValue,
+    FocusSignals,
+    FocusMatchRule,
+    FocusMonitor,
+    FocusMemberSearch,
+    FocusObjectQuery,
+    FocusFoundObjects,
+    ArgumentInput(char),
+    ArgumentDeleted,
+    PropertyValueInput(char),
+    PropertyValueDeleted,
+    MatchRuleInput(char),
+    MatchRuleDeleted,
+    SearchStarted,
+    SearchInput(char),
+    SearchDeleted,
+    SearchModeCycled,
+    SearchFinished,
+    SearchCleared,
 }
 
 impl Default for App {
     fn default() -> Self {
+        Self::new(Discovery::default())
+    }
+}
+
+impl App {
+    pub fn new(discovery: Discovery) -> Self {
+        let (sender, receiver) = mpsc::unbounded();
+        Self {
+            contexts: vec![BusContext::new(
+                EventSender::new(0, sender.clone()),
+                discovery,
+            )],
+            active: 0,
+            next_context_id: 1,
+            discovery,
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl BusContext {
+    fn new(events: EventSender, discovery: Discovery) -> Self {
         Self {
+            id: events.context,
+            bus: BusAddress::default(),
+            client: None,
+            bus_input: String::new(),
+            bus_input_error: None,
             focus: Section::BusFrame,
-            methods: Default::default(),
-            paths: Default::default(),
-            filter_aliases: true,
+            searches: HashMap::new(),
+            member_index: ListState::default(),
+            jump: None,
+            object_query: String::new(),
+            queried_interfaces: Vec::new(),
+            queried_services: 0,
+            found_objects: ListState::default(),
+            interfaces: Vec::new(),
+            members: ListState::default(),
+            expanded: HashMap::new(),
+            properties: ListState::default(),
+            member_view: MemberView::Methods,
+            paths: ListState::default(),
+            objects: HashMap::new(),
+            expanded_paths: HashSet::new(),
+            discovery,
+            discovered: 0,
+            watched_managers: HashSet::new(),
+            object_watches: Vec::new(),
+            arguments: String::new(),
+            call_result: None,
+            property_value: String::new(),
+            property_result: None,
+            hide_unique_names: true,
+            group_names: false,
             bus_name_state: ListState::default(),
+            name_changes: HashMap::new(),
+            name_watch: None,
+            activatable: HashSet::new(),
+            inactive: HashSet::new(),
+            name_details: HashMap::new(),
+            name_sort: NameSort::Name,
             log: ListState::default(),
+            signals: ListState::default(),
+            signal_monitor: None,
+            match_rule: None,
+            match_rule_input: String::new(),
+            match_rule_error: None,
+            traffic_view: TrafficView::Signals,
+            monitored_messages: ListState::default(),
+            bus_monitor: None,
+            error: None,
+            tasks: Vec::new(),
+            events,
         }
     }
 }
 
 impl App {
+    /// The context of the tab shown.
+    pub fn context(&self) -> &BusContext {
+        &self.contexts[self.active]
+    }
+
     pub fn reduce(&mut self, action: Action) {
         // action_to_events(action).into_iter().for_each(|event| {
         //     reduce_event(event, self);
@@ -89,91 +711,2549 @@ impl App {
         //     Action::None => {}
         //     a => self.reduce(reduce_event(self, action_to_events(a))),
         // }
-        match reduce_event(self, action_to_events(action)) {
+        match action {
+            Action::NewTab => {
+                let events = EventSender::new(self.next_context_id, self.sender.clone());
+                self.next_context_id += 1;
+                self.contexts.push(BusContext::new(events, self.discovery));
+                self.active = self.contexts.len() - 1;
+                self.reduce(Action::FocusBusAddress)
+            }
+            Action::CloseTab if self.contexts.len() > 1 => {
+                let mut context = self.contexts.remove(self.active);
+                if let Some(monitor) = context.bus_monitor.take() {
+                    monitor.abort();
+                }
+                context.tasks.iter().for_each(|task| task.abort.abort());
+                stop_object_watches(&mut context);
+                if let Some(watch) = context.name_watch.take() {
+                    watch.abort();
+                }
+                self.active = self.active.min(self.contexts.len() - 1);
+            }
+            Action::CloseTab => {}
+            Action::NextTab => self.active = (self.active + 1) % self.contexts.len(),
+            Action::PreviousTab => {
+                self.active = (self.active + self.contexts.len() - 1) % self.contexts.len()
+            }
+            action => self.reduce_in(self.context().id, action),
+        }
+    }
+
+    /// Reduces `action` in the context with the id `context`, even if it's not shown.
+    fn reduce_in(&mut self, context: usize, action: Action) {
+        match action {
+            Action::Batch(actions) => actions
+                .into_iter()
+                .for_each(|action| self.reduce_in(context, action)),
+            action => {
+                if let Some(bus_context) = self.contexts.iter().find(|c| c.id == context) {
+                    let event = action_to_events(bus_context, action);
+                    self.handle(context, event)
+                }
+            }
+        }
+    }
+
+    /// Events sent from background tasks and other threads, to be passed to `handle`.
+    pub fn events(&mut self) -> &mut UnboundedReceiver<(usize, AppEvent)> {
+        &mut self.receiver
+    }
+
+    /// Handles the events sent since the last call without waiting for more.
+    pub fn handle_pending_events(&mut self) {
+        while let Ok(Some((context, event))) = self.receiver.try_next() {
+            self.handle(context, event)
+        }
+    }
+
+    /// Applies `event` to the context with the id `context`, events of closed contexts are
+    /// dropped.
+    pub fn handle(&mut self, context: usize, event: AppEvent) {
+        let bus_context = match self.contexts.iter_mut().find(|c| c.id == context) {
+            Some(bus_context) => bus_context,
+            None => return,
+        };
+
+        match reduce_event(bus_context, event) {
             Action::None => {}
             a => {
-                self.log.entries.push(LogEntry::ActionEntry(a.to_owned()));
-                self.reduce(a)
+                bus_context.log_entry(LogEntry::ActionEntry(a.to_owned()));
+                self.reduce_in(context, a)
+            }
+        }
+    }
+}
+
+impl BusContext {
+    fn log_entry(&mut self, entry: LogEntry) {
+        if self.log.entries.len() >= MAX_LOG_ENTRIES {
+            self.log.entries.remove(0);
+        }
+        self.log.entries.push(entry);
+    }
+
+    /// Builds the action calling the selected method with the typed arguments.
+    pub fn call_selected_method(&self) -> Action {
+        match (
+            selected_bus_name(self),
+            self.paths.selected_entry(),
+            self.selected_method(),
+        ) {
+            (Some(bus_name), Some(path), Some(method)) => Action::CallMethod {
+                bus_name,
+                path: path.to_owned(),
+                interface: method.interface.to_owned(),
+                method: method.name.to_owned(),
+                arguments: self.arguments.to_owned(),
+            },
+            _ => Action::None,
+        }
+    }
+
+    /// The method highlighted in the interface tree, if a method is.
+    pub fn selected_method(&self) -> Option<&Method> {
+        match self.members.selected_entry()? {
+            MemberRow::Method(interface, method) => {
+                self.interfaces[*interface].methods.get(*method)
+            }
+            _ => None,
+        }
+    }
+
+    /// Interfaces not expanded or collapsed by hand are expanded, except for the standard
+    /// ones every object implements.
+    pub fn is_expanded(&self, interface: &Interface) -> bool {
+        self.expanded
+            .get(&interface.name)
+            .copied()
+            .unwrap_or_else(|| !interface.name.starts_with("org.freedesktop.DBus."))
+    }
+
+    pub fn is_monitoring(&self) -> bool {
+        self.bus_monitor.is_some()
+    }
+
+    /// Whether the search prompt of the focused pane takes the keys typed.
+    pub fn is_searching(&self) -> bool {
+        self.searches
+            .get(&self.focus)
+            .is_some_and(|search| search.editing)
+    }
+
+    /// Builds the action looking for the objects implementing the interfaces typed.
+    pub fn find_objects(&self) -> Action {
+        Action::FindObjects {
+            interfaces: self
+                .object_query
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|interface| !interface.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+
+    /// Builds the action showing the selected found object with the first interface queried
+    /// that isn't a standard one.
+    pub fn show_selected_object(&self) -> Action {
+        let object = match self.found_objects.selected_entry() {
+            Some(object) => object,
+            None => return Action::None,
+        };
+        let interface = self
+            .queried_interfaces
+            .iter()
+            .find(|interface| !interface.starts_with("org.freedesktop.DBus."))
+            .or_else(|| self.queried_interfaces.first());
+        match interface {
+            Some(interface) => Action::JumpToMember(IndexedMember {
+                service: object.service.to_owned(),
+                path: object.path.to_owned(),
+                interface: interface.to_owned(),
+                kind: MemberKind::Interface,
+                name: interface.to_owned(),
+            }),
+            None => Action::None,
+        }
+    }
+
+    /// Builds the action showing the member selected in the member search.
+    pub fn jump_to_selected_member(&self) -> Action {
+        match self.member_index.selected_entry() {
+            Some(member) => Action::JumpToMember(member.to_owned()),
+            None => Action::None,
+        }
+    }
+
+    /// Builds the action starting the service of the selected name if it's not running.
+    pub fn activate_selected_name(&self) -> Action {
+        match selected_bus_name(self) {
+            Some(bus_name) if self.inactive.contains(&bus_name) => {
+                Action::ActivateBusName { bus_name }
             }
+            _ => Action::None,
+        }
+    }
+
+    /// Builds the action setting the selected property to the typed value.
+    pub fn set_selected_property(&self) -> Action {
+        match (
+            selected_bus_name(self),
+            self.paths.selected_entry(),
+            self.properties.selected_entry(),
+        ) {
+            (Some(bus_name), Some(path), Some(property)) => Action::SetProperty {
+                bus_name,
+                path: path.to_owned(),
+                interface: property.interface.to_owned(),
+                name: property.name.to_owned(),
+                signature: property.signature.to_owned(),
+                value: self.property_value.to_owned(),
+            },
+            _ => Action::None,
         }
     }
 }
 
-pub fn action_to_events(a: Action) -> AppEvent {
+pub fn action_to_events(context: &BusContext, a: Action) -> AppEvent {
     match a {
-        Action::LoadBusNames => AppEvent::BusNamesLoaded(DBusClient::default().list_names()),
-        Action::Quit => todo!(),
-        Action::LoadPaths { bus_name } => match DBusClient::default().get_paths(&bus_name) {
-            Ok(paths) => AppEvent::PathsLoaded(paths),
+        Action::Connect { address } => {
+            let events = context.events.clone();
+            spawn(context, "connection", async move {
+                match connect(&address, events).await {
+                    Ok((address, client, signal_monitor)) => AppEvent::Connected {
+                        address,
+                        client,
+                        signal_monitor,
+                    },
+                    Err(e) => AppEvent::ConnectFailed(e),
+                }
+            })
+        }
+        Action::FocusBusAddress => AppEvent::FocusBusAddress,
+        Action::InputBusAddress(c) => AppEvent::BusAddressInput(c),
+        Action::DeleteBusAddress => AppEvent::BusAddressDeleted,
+        // a peer has no bus daemon to ask for names, everything goes to the peer itself
+        Action::LoadBusNames if context.bus.is_peer() => {
+            AppEvent::BusNamesLoaded(vec![PEER_NAME.to_string()])
+        }
+        Action::LoadBusNames => match client(context) {
+            Ok(client) => spawn(context, "bus names", async move {
+                match client.list_names().await {
+                    Ok(bus_names) => AppEvent::BusNamesLoaded(bus_names),
+                    Err(e) => AppEvent::Error(e),
+                }
+            }),
+            Err(e) => AppEvent::Error(e),
+        },
+        // each name is reported on its own, the list is usable before all are known
+        Action::LoadNameDetails { names } => match client(context) {
+            Ok(client) => {
+                let events = context.events.clone();
+                spawn(context, "name details", async move {
+                    let mut details = stream::iter(names)
+                        .map(|name| {
+                            let client = client.clone();
+                            async move { (client.get_name_details(&name).await, name) }
+                        })
+                        .buffer_unordered(16);
+                    while let Some((result, name)) = details.next().await {
+                        // the name vanished meanwhile, `NameOwnerChanged` tells about that
+                        if let Ok(details) = result {
+                            events.send(AppEvent::NameDetailsLoaded { name, details });
+                        }
+                    }
+                    AppEvent::None
+                })
+            }
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::SortBusNames => AppEvent::BusNamesSorted,
+        Action::ToggleNameGroups => AppEvent::NameGroupsToggled,
+        Action::ToggleUniqueNames => AppEvent::UniqueNamesToggled,
+        Action::LoadActivatableNames => match client(context) {
+            Ok(client) => spawn(context, "activatable names", async move {
+                match client.list_activatable_names().await {
+                    Ok(bus_names) => AppEvent::ActivatableNamesLoaded(bus_names),
+                    Err(e) => AppEvent::Error(e),
+                }
+            }),
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::ActivateBusName { bus_name } => match client(context) {
+            Ok(client) => spawn(context, "activation", async move {
+                match client.start_service_by_name(&bus_name).await {
+                    Ok(_) => AppEvent::ServiceStarted(bus_name),
+                    Err(e) => AppEvent::Error(e),
+                }
+            }),
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::WatchBusNames => match client(context) {
+            Ok(client) => {
+                let events = context.events.clone();
+                let (watch, registration) = AbortHandle::new_pair();
+                async_std::task::spawn(Abortable::new(
+                    async move {
+                        let mut changes = match client.receive_name_owner_changes().await {
+                            Ok(changes) => changes.boxed(),
+                            Err(e) => {
+                                events.send(AppEvent::Error(e));
+                                return;
+                            }
+                        };
+                        // listed once subscribed, so no change in between is missed
+                        events.send(match client.list_names().await {
+                            Ok(bus_names) => AppEvent::BusNamesLoaded(bus_names),
+                            Err(e) => AppEvent::Error(e),
+                        });
+                        while let Some(change) = changes.next().await {
+                            if !events.send(AppEvent::NameOwnerChanged(change)) {
+                                break;
+                            }
+                        }
+                    },
+                    registration,
+                ));
+                AppEvent::BusNamesWatched(watch)
+            }
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::ExpireNameChange { name, at } => {
+            let events = context.events.clone();
+            async_std::task::spawn(async move {
+                async_std::task::sleep(NAME_HIGHLIGHT).await;
+                events.send(AppEvent::NameChangeExpired { name, at });
+            });
+            AppEvent::None
+        }
+        // the main loop stops before reducing it
+        Action::Quit => AppEvent::None,
+        Action::NewTab
+        | Action::CloseTab
+        | Action::NextTab
+        | Action::PreviousTab
+        | Action::Batch(_) => unreachable!("handled by App::reduce"),
+        Action::LoadPaths { bus_name } => match client(context) {
+            Ok(client) => spawn(context, "paths", {
+                let discovery = context.discovery;
+                async move {
+                    let nodes = client.discover(&bus_name, "/", 1, &discovery, |_| {}).await;
+                    AppEvent::PathsLoaded(nodes)
+                }
+            }),
             Err(e) => AppEvent::Error(e),
         },
         Action::SelectLastBusName => AppEvent::SelectPreviousBusName,
         Action::SelectNextBusName => AppEvent::SelectNextBusName,
         Action::None => AppEvent::None,
-        Action::Resize { section, rows } => todo!(),
         Action::FocusBusNames => AppEvent::FocusBusNames,
         Action::FocusPaths => AppEvent::FocusPaths,
+        Action::FocusMethods => AppEvent::FocusMethods,
+        Action::FocusArguments => AppEvent::FocusArguments,
+        Action::FocusProperties => AppEvent::FocusProperties,
+        Action::FocusPropertyValue => AppEvent::FocusPropertyValue,
+        Action::FocusSignals => AppEvent::FocusSignals,
+        Action::FocusMatchRule => AppEvent::FocusMatchRule,
+        Action::FocusMonitor => AppEvent::FocusMonitor,
+        Action::FocusMemberSearch => AppEvent::FocusMemberSearch,
+        Action::FocusObjectQuery => AppEvent::FocusObjectQuery,
+        Action::FocusFoundObjects => AppEvent::FocusFoundObjects,
+        Action::CancelTasks => AppEvent::TasksCancelled,
+        Action::DismissError => AppEvent::ErrorDismissed,
+        Action::Refresh => {
+            if let Some(client) = &context.client {
+                client.clear_cache();
+            }
+            AppEvent::CacheCleared
+        }
+        Action::StartBusMonitor => {
+            let bus = context.bus.to_owned();
+            let events = context.events.clone();
+            spawn(context, "bus monitor", async move {
+                match start_bus_monitor(&bus, events).await {
+                    Ok(monitor) => AppEvent::BusMonitorStarted(monitor),
+                    Err(e) => AppEvent::Error(e),
+                }
+            })
+        }
+        Action::LoadInterfaces { bus_name, path } => match client(context) {
+            Ok(client) => spawn(context, "interfaces", async move {
+                match client.get_interfaces(&bus_name, &path).await {
+                    Ok(interfaces) => AppEvent::InterfacesLoaded(interfaces),
+                    Err(e) => AppEvent::Error(e),
+                }
+            }),
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::CallMethod {
+            bus_name,
+            path,
+            interface,
+            method,
+            arguments,
+        } => match client(context) {
+            Ok(client) => spawn(context, "call", async move {
+                match call_method(&client, &bus_name, &path, &interface, &method, &arguments).await
+                {
+                    Ok(reply) => AppEvent::MethodCalled(reply),
+                    Err(e) => AppEvent::MethodCallFailed(e),
+                }
+            }),
+            Err(e) => AppEvent::MethodCallFailed(e),
+        },
+        Action::LoadProperties { bus_name, path } => match client(context) {
+            Ok(client) => spawn(context, "properties", async move {
+                match client.get_properties(&bus_name, &path).await {
+                    Ok(properties) => AppEvent::PropertiesLoaded(properties),
+                    Err(e) => AppEvent::Error(e),
+                }
+            }),
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::GetProperty {
+            bus_name,
+            path,
+            interface,
+            name,
+        } => match client(context) {
+            Ok(client) => spawn(context, "property", async move {
+                match client
+                    .get_property(&bus_name, &path, &interface, &name)
+                    .await
+                {
+                    Ok(value) => AppEvent::PropertyLoaded {
+                        interface,
+                        name,
+                        value: format_value(&value),
+                    },
+                    Err(e) => AppEvent::Error(e),
+                }
+            }),
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::SetProperty {
+            bus_name,
+            path,
+            interface,
+            name,
+            signature,
+            value,
+        } => match client(context) {
+            Ok(client) => spawn(context, "property", async move {
+                match set_property(
+                    &client, &bus_name, &path, &interface, &name, &signature, &value,
+                )
+                .await
+                {
+                    Ok(()) => AppEvent::PropertySet {
+                        bus_name,
+                        path,
+                        interface,
+                        name,
+                    },
+                    Err(e) => AppEvent::PropertySetFailed(e),
+                }
+            }),
+            Err(e) => AppEvent::PropertySetFailed(e),
+        },
+        Action::SelectLastPath => AppEvent::SelectPreviousPath,
+        Action::SelectNextPath => AppEvent::SelectNextPath,
+        Action::ExpandPath { recursive } => AppEvent::PathExpanded { recursive },
+        Action::CollapsePath => AppEvent::PathCollapsed,
+        Action::TogglePath => match context.paths.selected_entry() {
+            Some(path) if context.expanded_paths.contains(path) => AppEvent::PathCollapsed,
+            Some(_) => AppEvent::PathExpanded { recursive: false },
+            None => AppEvent::None,
+        },
+        Action::LoadChildren {
+            bus_name,
+            path,
+            recursive,
+        } => match client(context) {
+            Ok(client) => {
+                let discovery = context.discovery;
+                let events = context.events.clone();
+                let key = Some(path.to_owned());
+                spawn_for(context, "children", key, async move {
+                    let depth = if recursive { discovery.max_depth } else { 1 };
+                    let nodes = client
+                        .discover(&bus_name, &path, depth, &discovery, |nodes| {
+                            events.send(AppEvent::NodesDiscovered {
+                                bus_name: bus_name.to_owned(),
+                                nodes: nodes.to_vec(),
+                                recursive,
+                            });
+                        })
+                        .await;
+                    AppEvent::ChildrenLoaded {
+                        path,
+                        nodes,
+                        recursive,
+                    }
+                })
+            }
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::WatchObjects { bus_name, paths } => match client(context) {
+            Ok(client) => {
+                let events = context.events.clone();
+                let (watch, registration) = AbortHandle::new_pair();
+                let watched_name = bus_name.to_owned();
+                async_std::task::spawn(Abortable::new(
+                    async move {
+                        let mut streams = Vec::new();
+                        for path in paths {
+                            match client.receive_object_changes(&watched_name, &path).await {
+                                Ok(changes) => streams.push(changes.boxed()),
+                                Err(e) => {
+                                    events.send(AppEvent::Error(e));
+                                }
+                            }
+                        }
+                        let mut changes = stream::select_all(streams);
+                        while let Some(change) = changes.next().await {
+                            let event = AppEvent::ObjectsChanged {
+                                bus_name: watched_name.to_owned(),
+                                change,
+                            };
+                            if !events.send(event) {
+                                break;
+                            }
+                        }
+                    },
+                    registration,
+                ));
+                AppEvent::ObjectsWatched { bus_name, watch }
+            }
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::SelectLastMember => AppEvent::SelectPreviousMember,
+        Action::SelectNextMember => AppEvent::SelectNextMember,
+        Action::ExpandInterface => AppEvent::InterfaceExpanded(true),
+        Action::CollapseInterface => AppEvent::InterfaceExpanded(false),
+        Action::ToggleInterface => match context.members.selected_entry() {
+            Some(row) => AppEvent::InterfaceExpanded(
+                !context.is_expanded(&context.interfaces[row.interface()]),
+            ),
+            None => AppEvent::None,
+        },
+        Action::InputArgument(c) => AppEvent::ArgumentInput(c),
+        Action::DeleteArgument => AppEvent::ArgumentDeleted,
+        Action::SelectLastProperty => AppEvent::SelectPreviousProperty,
+        Action::SelectNextProperty => AppEvent::SelectNextProperty,
+        Action::InputPropertyValue(c) => AppEvent::PropertyValueInput(c),
+        Action::DeletePropertyValue => AppEvent::PropertyValueDeleted,
+        Action::SelectLastSignal => AppEvent::SelectPreviousSignal,
+        Action::SelectNextSignal => AppEvent::SelectNextSignal,
+        Action::SelectLastMonitoredMessage => AppEvent::SelectPreviousMonitoredMessage,
+        Action::SelectNextMonitoredMessage => AppEvent::SelectNextMonitoredMessage,
+        // services are indexed a few at a time, each is listed once it's done
+        Action::IndexMembers => match client(context) {
+            Ok(client) => {
+                let services = services_to_index(context);
+                let discovery = context.discovery;
+                let events = context.events.clone();
+                spawn(context, "member index", async move {
+                    let mut indexed = stream::iter(services)
+                        .map(|service| {
+                            let client = client.clone();
+                            async move { client.index_members(&service, &discovery).await }
+                        })
+                        .buffer_unordered(4);
+                    while let Some(members) = indexed.next().await {
+                        events.send(AppEvent::MembersIndexed(members));
+                    }
+                    AppEvent::None
+                })
+            }
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::InputObjectQuery(c) => AppEvent::ObjectQueryInput(c),
+        Action::DeleteObjectQuery => AppEvent::ObjectQueryDeleted,
+        Action::FindObjects { interfaces } => AppEvent::ObjectQueryStarted { interfaces },
+        // like the member index, each service is listed once it's done
+        Action::ScanObjects {
+            services,
+            interfaces,
+        } => match client(context) {
+            Ok(client) => {
+                let discovery = context.discovery;
+                let events = context.events.clone();
+                spawn(context, "object query", async move {
+                    let mut found = stream::iter(services)
+                        .map(|service| {
+                            let client = client.clone();
+                            let interfaces = &interfaces;
+                            async move {
+                                let paths =
+                                    client.find_objects(&service, interfaces, &discovery).await;
+                                (service, paths)
+                            }
+                        })
+                        .buffer_unordered(4);
+                    while let Some((service, paths)) = found.next().await {
+                        let objects = paths
+                            .into_iter()
+                            .map(|path| FoundObject {
+                                service: service.to_owned(),
+                                path,
+                                properties: None,
+                            })
+                            .collect();
+                        events.send(AppEvent::ObjectsFound(objects));
+                    }
+                    AppEvent::None
+                })
+            }
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::SelectLastFoundObject => AppEvent::SelectPreviousFoundObject,
+        Action::SelectNextFoundObject => AppEvent::SelectNextFoundObject,
+        Action::CompareFoundObjects => match client(context) {
+            Ok(client) => {
+                let objects: Vec<(String, String)> = context
+                    .found_objects
+                    .entries
+                    .iter()
+                    .map(|object| (object.service.to_owned(), object.path.to_owned()))
+                    .collect();
+                let interfaces = context.queried_interfaces.to_owned();
+                let concurrency = context.discovery.concurrency.max(1);
+                let events = context.events.clone();
+                spawn(context, "comparison", async move {
+                    let mut compared = stream::iter(objects)
+                        .map(|(service, path)| {
+                            let client = client.clone();
+                            let interfaces = &interfaces;
+                            async move {
+                                let properties =
+                                    client.get_properties_of(&service, &path, interfaces).await;
+                                AppEvent::ObjectCompared {
+                                    service,
+                                    path,
+                                    properties,
+                                }
+                            }
+                        })
+                        .buffer_unordered(concurrency);
+                    while let Some(event) = compared.next().await {
+                        events.send(event);
+                    }
+                    AppEvent::None
+                })
+            }
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::SelectLastIndexedMember => AppEvent::SelectPreviousIndexedMember,
+        Action::SelectNextIndexedMember => AppEvent::SelectNextIndexedMember,
+        // the path and its ancestors with their children, enough to show it in the tree
+        Action::JumpToMember(member) => match client(context) {
+            Ok(client) => spawn(context, "paths", {
+                let discovery = context.discovery;
+                async move {
+                    let mut paths = vec![member.path.as_str()];
+                    while let Some(parent) = paths.last().copied().and_then(parent_path) {
+                        paths.push(parent);
+                    }
+
+                    let mut visited = HashSet::new();
+                    let mut nodes = Vec::new();
+                    for path in paths.into_iter().rev() {
+                        let found = client
+                            .discover(&member.service, path, 1, &discovery, |_| {})
+                            .await;
+                        nodes.extend(
+                            found
+                                .into_iter()
+                                .filter(|node| visited.insert(node.path.to_owned())),
+                        );
+                    }
+                    AppEvent::JumpLoaded { member, nodes }
+                }
+            }),
+            Err(e) => AppEvent::Error(e),
+        },
+        Action::InputMatchRule(c) => AppEvent::MatchRuleInput(c),
+        Action::DeleteMatchRule => AppEvent::MatchRuleDeleted,
+        Action::Search => AppEvent::SearchStarted,
+        Action::InputSearch(c) => AppEvent::SearchInput(c),
+        Action::DeleteSearch => AppEvent::SearchDeleted,
+        Action::CycleSearchMode => AppEvent::SearchModeCycled,
+        Action::FinishSearch => AppEvent::SearchFinished,
+        Action::ClearSearch => AppEvent::SearchCleared,
+        Action::ApplyMatchRule { rule } => {
+            let (rule, monitor) = match (MatchRule::parse(&rule), &context.signal_monitor) {
+                (Ok(rule), Some(monitor)) => (rule, monitor.clone()),
+                (Err(e), _) => return AppEvent::MatchRuleFailed(e),
+                (_, None) => {
+                    return AppEvent::MatchRuleFailed(DBusConsoleError::dbus(
+                        "Signal monitor isn't running",
+                    ))
+                }
+            };
+            let previous = context.match_rule.to_owned();
+            spawn(context, "match rule", async move {
+                match monitor.set_rule(previous.as_ref(), &rule).await {
+                    Ok(()) => AppEvent::MatchRuleApplied(rule),
+                    Err(e) => AppEvent::MatchRuleFailed(e),
+                }
+            })
+        }
     }
 }
 
-fn reduce_event(app: &mut App, e: AppEvent) -> Action {
-    app.log.entries.push(LogEntry::AppEventEntry(e.to_owned()));
+/// Runs `future` in the background, the event it resolves to is sent back wrapped in
+/// `AppEvent::TaskFinished` unless the task is aborted before.
+fn spawn<F>(context: &BusContext, kind: &'static str, future: F) -> AppEvent
+where
+    F: Future<Output = AppEvent> + Send + 'static,
+{
+    spawn_for(context, kind, None, future)
+}
+
+/// Like `spawn`, but only replaces a running task of the same kind if it has the same `key`.
+fn spawn_for<F>(
+    context: &BusContext,
+    kind: &'static str,
+    key: Option<String>,
+    future: F,
+) -> AppEvent
+where
+    F: Future<Output = AppEvent> + Send + 'static,
+{
+    let id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+    let (abort, registration) = AbortHandle::new_pair();
+    let events = context.events.clone();
+
+    async_std::task::spawn(Abortable::new(
+        async move {
+            let event = future.await;
+            events.send(AppEvent::TaskFinished {
+                id,
+                event: Box::new(event),
+            });
+        },
+        registration,
+    ));
+
+    AppEvent::TaskStarted(Task {
+        id,
+        kind,
+        key,
+        abort,
+    })
+}
+
+fn client(context: &BusContext) -> DBusConsoleResult<DBusClient> {
+    context
+        .client
+        .to_owned()
+        .ok_or_else(|| DBusConsoleError::dbus(format!("Not connected to {}", context.bus)))
+}
+
+/// Connects to `address` for browsing and, on a separate connection, for signals.
+async fn connect(
+    address: &str,
+    events: EventSender,
+) -> DBusConsoleResult<(BusAddress, DBusClient, SignalMonitor)> {
+    let address = BusAddress::parse(address)?;
+    let client = DBusClient::new(address.connect().await?);
+    // a peer has no bus daemon telling about name owners, its data is kept until refreshed
+    if !address.is_peer() {
+        client.watch_name_owners().await?;
+    }
+    let signal_monitor = SignalMonitor::start(&address, events).await?;
+
+    Ok((address, client, signal_monitor))
+}
+
+async fn call_method(
+    client: &DBusClient,
+    bus_name: &str,
+    path: &str,
+    interface: &str,
+    method: &str,
+    arguments: &str,
+) -> DBusConsoleResult<String> {
+    let signature = client
+        .get_signature(bus_name, path, interface, method)
+        .await?
+        .unwrap_or_default();
+    let arguments = parse_arguments(&signature, arguments)?;
+
+    let reply = if arguments.fields().is_empty() {
+        client
+            .call_function(bus_name, path, interface, method, &())
+            .await?
+    } else {
+        client
+            .call_function(bus_name, path, interface, method, &arguments)
+            .await?
+    };
+
+    format_body(&reply)
+}
+
+async fn set_property(
+    client: &DBusClient,
+    bus_name: &str,
+    path: &str,
+    interface: &str,
+    name: &str,
+    signature: &str,
+    value: &str,
+) -> DBusConsoleResult<()> {
+    let value = parse_value(signature, value)?;
+    client
+        .set_property(bus_name, path, interface, name, value)
+        .await
+}
+
+fn reduce_event(context: &mut BusContext, e: AppEvent) -> Action {
+    // monitored messages have a pane of their own, they would drown everything else here, and
+    // the event a task finished with is logged on its own. The connections a bus is connected
+    // with would stay open as long as the log keeps them, the connect action is logged instead.
+    if !matches!(
+        e,
+        AppEvent::MessageMonitored(_) | AppEvent::TaskFinished { .. } | AppEvent::Connected { .. }
+    ) {
+        context.log_entry(LogEntry::AppEventEntry(e.to_owned()));
+    }
     match e {
+        AppEvent::TaskStarted(task) => {
+            context.tasks.retain(|running| {
+                let replaced = running.kind == task.kind && running.key == task.key;
+                if replaced {
+                    running.abort.abort();
+                }
+                !replaced
+            });
+            context.tasks.push(task);
+            Action::None
+        }
+        AppEvent::TaskFinished { id, event } => {
+            match context.tasks.iter().position(|task| task.id == id) {
+                Some(index) => {
+                    context.tasks.remove(index);
+                    reduce_event(context, *event)
+                }
+                // cancelled or replaced meanwhile
+                None => Action::None,
+            }
+        }
+        AppEvent::TasksCancelled => {
+            context.tasks.drain(..).for_each(|task| task.abort.abort());
+            context.discovered = 0;
+            Action::None
+        }
+        AppEvent::CacheCleared => {
+            // crawled again the next time it's searched
+            abort_tasks(context, &["member index"]);
+            context.member_index.replace(Vec::new());
+            let path = context.paths.selected_entry().cloned();
+            match (selected_bus_name(context), path) {
+                (Some(bus_name), Some(path)) => {
+                    let mut actions = vec![Action::LoadInterfaces {
+                        bus_name: bus_name.to_owned(),
+                        path: path.to_owned(),
+                    }];
+                    if context.expanded_paths.contains(&path) {
+                        actions.push(Action::LoadChildren {
+                            bus_name,
+                            path,
+                            recursive: false,
+                        });
+                    }
+                    Action::Batch(actions)
+                }
+                (Some(bus_name), None) => Action::LoadPaths { bus_name },
+                _ => Action::None,
+            }
+        }
+        AppEvent::Connected {
+            address,
+            client,
+            signal_monitor,
+        } => {
+            context.bus = address;
+            context.client = Some(client);
+            context.signal_monitor = Some(signal_monitor);
+            context.bus_input_error = None;
+            if context.focus == Section::BusAddress {
+                context.focus = Section::BusFrame;
+            }
+
+            // nothing of the previous bus is kept but the match rule, which is added again
+            let rule = context
+                .match_rule
+                .take()
+                .map(|rule| rule.to_input())
+                .unwrap_or_default();
+            context.bus_name_state.replace(Vec::new());
+            context.name_changes.clear();
+            context.activatable.clear();
+            context.inactive.clear();
+            context.name_details.clear();
+            abort_tasks(context, &["member index", "object query", "comparison"]);
+            context.member_index.replace(Vec::new());
+            context.found_objects.replace(Vec::new());
+            if let Some(watch) = context.name_watch.take() {
+                watch.abort();
+            }
+            context.paths.replace(Vec::new());
+            stop_object_watches(context);
+            context.interfaces = Vec::new();
+            context.members.replace(Vec::new());
+            context.properties.replace(Vec::new());
+            context.signals.replace(Vec::new());
+            context.monitored_messages.replace(Vec::new());
+            context.call_result = None;
+            context.property_result = None;
+
+            let mut actions = vec![Action::ApplyMatchRule { rule }];
+            // a peer has no bus daemon to tell about names, the watch lists them otherwise
+            if context.bus.is_peer() {
+                actions.push(Action::LoadBusNames);
+            } else {
+                actions.push(Action::WatchBusNames);
+                actions.push(Action::LoadActivatableNames);
+            }
+            if let Some(monitor) = context.bus_monitor.take() {
+                monitor.abort();
+                actions.push(Action::StartBusMonitor);
+            }
+            Action::Batch(actions)
+        }
+        AppEvent::ConnectFailed(e) => {
+            context.bus_input_error = Some(e);
+            Action::None
+        }
+        AppEvent::FocusBusAddress => {
+            context.bus_input = context.bus.to_string();
+            context.bus_input_error = None;
+            context.focus = Section::BusAddress;
+            Action::None
+        }
+        AppEvent::BusAddressInput(c) => {
+            context.bus_input.push(c);
+            Action::None
+        }
+        AppEvent::BusAddressDeleted => {
+            context.bus_input.pop();
+            Action::None
+        }
         AppEvent::BusNamesLoaded(bus_names) => {
-            app.bus_name_state.entries = bus_names;
+            let selected = selected_name_row(context);
+            let mut inactive: Vec<String> = context
+                .activatable
+                .iter()
+                .filter(|name| !bus_names.contains(name))
+                .cloned()
+                .collect();
+            inactive.sort();
+            context.inactive = inactive.iter().cloned().collect();
+            let running = bus_names.to_owned();
+            context.bus_name_state.entries = bus_names;
+            context.bus_name_state.entries.extend(inactive);
+            // changes received before stay highlighted, vanished names are listed until then
+            for (name, (change, _)) in &context.name_changes {
+                if *change == NameChange::Vanished && !context.bus_name_state.entries.contains(name)
+                {
+                    context.bus_name_state.entries.push(name.to_owned());
+                }
+            }
+            context.name_details.clear();
+            sort_bus_names(context);
+            reselect_bus_name(context, selected);
+            Action::LoadNameDetails { names: running }
+        }
+        AppEvent::ActivatableNamesLoaded(mut bus_names) => {
+            let selected = selected_name_row(context);
+            bus_names.sort();
+            for name in &bus_names {
+                if !context.bus_name_state.entries.contains(name) {
+                    context.bus_name_state.entries.push(name.to_owned());
+                    context.inactive.insert(name.to_owned());
+                }
+            }
+            context.activatable = bus_names.into_iter().collect();
+            sort_bus_names(context);
+            reselect_bus_name(context, selected);
+            Action::None
+        }
+        AppEvent::NameDetailsLoaded { name, details } => {
+            if !context.bus_name_state.entries.contains(&name) || context.inactive.contains(&name) {
+                return Action::None;
+            }
+            let selected = selected_name_row(context);
+            context.name_details.insert(name, details);
+            sort_bus_names(context);
+            reselect_bus_name(context, selected);
+            Action::None
+        }
+        AppEvent::NameGroupsToggled => {
+            let selected = selected_name_row(context);
+            context.group_names = !context.group_names;
+            reselect_bus_name(context, selected);
+            Action::None
+        }
+        AppEvent::UniqueNamesToggled => {
+            let selected = selected_name_row(context);
+            context.hide_unique_names = !context.hide_unique_names;
+            reselect_bus_name(context, selected);
+            Action::None
+        }
+        AppEvent::BusNamesSorted => {
+            let selected = selected_name_row(context);
+            context.name_sort = context.name_sort.next();
+            sort_bus_names(context);
+            reselect_bus_name(context, selected);
+            Action::None
+        }
+        AppEvent::ServiceStarted(bus_name) => {
+            context.inactive.remove(&bus_name);
+            match selected_bus_name(context) {
+                Some(selected) if selected == bus_name => Action::LoadPaths { bus_name },
+                _ => Action::None,
+            }
+        }
+        AppEvent::BusNamesWatched(watch) => {
+            if let Some(previous) = context.name_watch.replace(watch) {
+                previous.abort();
+            }
+            Action::None
+        }
+        AppEvent::NameOwnerChanged(change) => {
+            let selected = selected_name_row(context);
+            let change_kind = match (&change.old_owner, &change.new_owner) {
+                (None, Some(_)) => NameChange::Appeared,
+                // kept in the list until the highlight ends
+                (Some(_), None) => NameChange::Vanished,
+                _ => NameChange::OwnerChanged,
+            };
+            if change_kind == NameChange::Appeared {
+                context.inactive.remove(&change.name);
+            }
+            if change_kind != NameChange::Vanished
+                && !context.bus_name_state.entries.contains(&change.name)
+            {
+                context.bus_name_state.entries.push(change.name.to_owned());
+            }
+            let at = Instant::now();
+            context
+                .name_changes
+                .insert(change.name.to_owned(), (change_kind, at));
+            sort_bus_names(context);
+            reselect_bus_name(context, selected);
+
+            let expire = Action::ExpireNameChange {
+                name: change.name.to_owned(),
+                at,
+            };
+            match change_kind {
+                NameChange::Vanished => expire,
+                _ => {
+                    context.name_details.remove(&change.name);
+                    Action::Batch(vec![
+                        expire,
+                        Action::LoadNameDetails {
+                            names: vec![change.name],
+                        },
+                    ])
+                }
+            }
+        }
+        AppEvent::NameChangeExpired { name, at } => {
+            match context.name_changes.get(&name) {
+                // changed again meanwhile, that change's highlight ends later
+                Some((_, changed)) if *changed != at => {}
+                // listed as long as it can be started again
+                Some((NameChange::Vanished, _)) if context.activatable.contains(&name) => {
+                    context.name_changes.remove(&name);
+                    context.name_details.remove(&name);
+                    context.inactive.insert(name);
+                }
+                Some((NameChange::Vanished, _)) => {
+                    let selected = selected_name_row(context);
+                    context.name_details.remove(&name);
+                    context
+                        .bus_name_state
+                        .entries
+                        .retain(|entry| *entry != name);
+                    context.name_changes.remove(&name);
+                    reselect_bus_name(context, selected);
+                }
+                _ => {
+                    context.name_changes.remove(&name);
+                }
+            }
             Action::None
         }
         AppEvent::None => Action::None,
-        AppEvent::MethodsLoaded(methods) => {
-            app.methods = methods;
+        AppEvent::InterfacesLoaded(interfaces) => {
+            context.interfaces = interfaces;
+            // unless another path was selected meanwhile
+            let jump = context
+                .jump
+                .take()
+                .filter(|member| context.paths.selected_entry() == Some(&member.path));
+            if let Some(member) = &jump {
+                context.expanded.insert(member.interface.to_owned(), true);
+            }
+            let rows = member_rows(context);
+            context.members.replace(rows);
+            if let Some(member) = &jump {
+                context.members.selected = context
+                    .members
+                    .entries
+                    .iter()
+                    .position(|row| is_member(context, row, member))
+                    .map(|index| index as u32);
+            }
+            match (selected_bus_name(context), context.paths.selected_entry()) {
+                (Some(bus_name), Some(path)) => Action::LoadProperties {
+                    bus_name,
+                    path: path.to_owned(),
+                },
+                _ => Action::None,
+            }
+        }
+        AppEvent::PathsLoaded(nodes) => {
+            context.objects = nodes
+                .into_iter()
+                .map(|node| (node.path.to_owned(), node))
+                .collect();
+            context.expanded_paths = HashSet::from([String::from("/")]);
+            context.discovered = 0;
+            context.paths.replace(Vec::new());
+            update_paths(context);
+            context.interfaces = Vec::new();
+            context.members.replace(Vec::new());
+            context.properties.replace(Vec::new());
+            stop_object_watches(context);
+            watch_object_managers(context)
+        }
+        AppEvent::ChildrenLoaded {
+            path,
+            nodes,
+            recursive,
+        } => {
+            for node in nodes {
+                if recursive && !node.children.is_empty() {
+                    context.expanded_paths.insert(node.path.to_owned());
+                }
+                context.objects.insert(node.path.to_owned(), node);
+            }
+            context.expanded_paths.insert(path);
+            context.discovered = 0;
+            update_paths(context);
+            watch_object_managers(context)
+        }
+        AppEvent::NodesDiscovered {
+            bus_name,
+            nodes,
+            recursive,
+        } => {
+            if selected_bus_name(context).as_ref() != Some(&bus_name) {
+                return Action::None;
+            }
+            context.discovered += nodes.len();
+            for node in nodes {
+                if recursive && !node.children.is_empty() {
+                    context.expanded_paths.insert(node.path.to_owned());
+                }
+                context.objects.insert(node.path.to_owned(), node);
+            }
+            update_paths(context);
+            watch_object_managers(context)
+        }
+        AppEvent::ObjectsWatched { bus_name, watch } => {
+            // the tree was replaced while the watch started
+            if selected_bus_name(context).as_ref() == Some(&bus_name) {
+                context.object_watches.push(watch);
+            } else {
+                watch.abort();
+            }
             Action::None
         }
-        AppEvent::PathsLoaded(paths) => {
-            app.paths = paths;
+        AppEvent::ObjectsChanged { bus_name, change } => {
+            if selected_bus_name(context).as_ref() != Some(&bus_name) {
+                return Action::None;
+            }
+            let path = match change {
+                ObjectChange::InterfacesAdded { path, interfaces } => {
+                    add_interfaces(&mut context.objects, &path, interfaces);
+                    path
+                }
+                ObjectChange::InterfacesRemoved { path, interfaces } => {
+                    remove_interfaces(&mut context.objects, &path, &interfaces);
+                    path
+                }
+            };
+            update_paths(context);
+            load_interfaces(context, context.paths.selected_entry() == Some(&path))
+        }
+        AppEvent::PathExpanded { recursive } => {
+            let path = match context.paths.selected_entry() {
+                Some(path) => path.to_owned(),
+                None => return Action::None,
+            };
+            let children_known = context.objects.get(&path).map(|node| {
+                node.children
+                    .iter()
+                    .all(|child| context.objects.contains_key(child))
+            });
+            match (recursive, children_known, selected_bus_name(context)) {
+                (false, Some(true), _) => {
+                    context.expanded_paths.insert(path);
+                    update_paths(context);
+                    Action::None
+                }
+                (_, _, Some(bus_name)) => Action::LoadChildren {
+                    bus_name,
+                    path,
+                    recursive,
+                },
+                _ => Action::None,
+            }
+        }
+        AppEvent::PathCollapsed => {
+            let path = match context.paths.selected_entry() {
+                Some(path) => path.to_owned(),
+                None => return Action::None,
+            };
+            if context.expanded_paths.remove(&path) {
+                update_paths(context);
+                Action::None
+            } else {
+                // collapsing a collapsed path moves up to its parent instead
+                let parent = match path.rsplit_once('/') {
+                    Some(("", _)) if path != "/" => String::from("/"),
+                    Some((parent, _)) if !parent.is_empty() => parent.to_string(),
+                    _ => return Action::None,
+                };
+                let index = context.paths.entries.iter().position(|row| *row == parent);
+                let changed = index.is_some_and(|index| context.paths.select(index));
+                load_interfaces(context, changed)
+            }
+        }
+        AppEvent::PropertiesLoaded(properties) => {
+            context.properties.replace(properties);
             Action::None
         }
-        AppEvent::SelectNextBusName => select_next_bus_name(app),
-        AppEvent::SelectPreviousBusName => select_last_bus_name(app),
-        AppEvent::Error(_) => Action::None,
-        AppEvent::FocusBusNames => {
-            app.focus = Section::BusFrame;
+        AppEvent::PropertyLoaded {
+            interface,
+            name,
+            value,
+        } => {
+            if let Some(property) = context
+                .properties
+                .entries
+                .iter_mut()
+                .find(|property| property.interface == interface && property.name == name)
+            {
+                property.value = Some(value.to_owned());
+                property.error = None;
+            }
+            context.property_result = Some(Ok(value));
             Action::None
+        }
+        AppEvent::PropertySet {
+            bus_name,
+            path,
+            interface,
+            name,
+        } => Action::GetProperty {
+            bus_name,
+            path,
+            interface,
+            name,
         },
-        AppEvent::FocusPaths => {
-            app.focus = Section::BusPath;
+        AppEvent::PropertySetFailed(e) => {
+            context.property_result = Some(Err(e));
             Action::None
-        },
-    }
-}
-
-fn select_next_bus_name(app: &mut App) -> Action {
-    match app.bus_name_state.selected.as_ref() {
-        Some(index) => select_bus_name(app, *index as i32 + 1),
-        None => select_bus_name(app, 0),
-    }
-}
+        }
+        AppEvent::MethodCalled(reply) => {
+            context.call_result = Some(Ok(reply));
+            Action::None
+        }
+        AppEvent::MethodCallFailed(e) => {
+            context.call_result = Some(Err(e));
+            Action::None
+        }
+        AppEvent::SelectNextBusName => select_next_bus_name(context),
+        AppEvent::SelectPreviousBusName => select_last_bus_name(context),
+        AppEvent::SelectNextPath => {
+            let visible = visible_rows(context, &Section::BusPath);
+            let changed = context.paths.select_next(&visible);
+            load_interfaces(context, changed)
+        }
+        AppEvent::SelectPreviousPath => {
+            let visible = visible_rows(context, &Section::BusPath);
+            let changed = context.paths.select_previous(&visible);
+            load_interfaces(context, changed)
+        }
+        AppEvent::SelectNextMember => {
+            let visible = visible_rows(context, &Section::Methods);
+            context.members.select_next(&visible);
+            Action::None
+        }
+        AppEvent::SelectPreviousMember => {
+            let visible = visible_rows(context, &Section::Methods);
+            context.members.select_previous(&visible);
+            Action::None
+        }
+        AppEvent::InterfaceExpanded(expanded) => {
+            if let Some(interface) = context.members.selected_entry().map(MemberRow::interface) {
+                let name = context.interfaces[interface].name.to_owned();
+                context.expanded.insert(name, expanded);
+                let rows = member_rows(context);
+                context.members.replace(rows);
+                // the interface stays highlighted, even when collapsed from one of its members
+                context.members.selected = context
+                    .members
+                    .entries
+                    .iter()
+                    .position(|row| *row == MemberRow::Interface(interface))
+                    .map(|index| index as u32);
+            }
+            Action::None
+        }
+        AppEvent::SignalReceived(signal) => {
+            if context.signals.entries.len() >= MAX_SIGNALS {
+                context.signals.entries.remove(0);
+                context.signals.selected = context
+                    .signals
+                    .selected
+                    .map(|index| index.saturating_sub(1));
+            }
+            context.signals.entries.push(signal);
+            Action::None
+        }
+        AppEvent::MatchRuleApplied(rule) => {
+            context.match_rule = Some(rule);
+            context.match_rule_error = None;
+            if context.focus == Section::MatchRule {
+                context.focus = Section::Signals;
+            }
+            Action::None
+        }
+        AppEvent::MatchRuleFailed(e) => {
+            context.match_rule_error = Some(e);
+            Action::None
+        }
+        AppEvent::SelectNextSignal => {
+            let visible = visible_rows(context, &Section::Signals);
+            context.signals.select_next(&visible);
+            Action::None
+        }
+        AppEvent::SelectPreviousSignal => {
+            let visible = visible_rows(context, &Section::Signals);
+            context.signals.select_previous(&visible);
+            Action::None
+        }
+        AppEvent::FocusSignals => {
+            context.focus = Section::Signals;
+            context.traffic_view = TrafficView::Signals;
+            Action::None
+        }
+        AppEvent::FocusMonitor => {
+            context.focus = Section::Monitor;
+            context.traffic_view = TrafficView::Monitor;
+            if context.is_monitoring() {
+                Action::None
+            } else {
+                Action::StartBusMonitor
+            }
+        }
+        AppEvent::BusMonitorStarted(monitor) => {
+            if let Some(previous) = context.bus_monitor.replace(monitor) {
+                previous.abort();
+            }
+            Action::None
+        }
+        AppEvent::MessageMonitored(message) => {
+            if context.monitored_messages.entries.len() >= MAX_MONITORED_MESSAGES {
+                context.monitored_messages.entries.remove(0);
+                context.monitored_messages.selected = context
+                    .monitored_messages
+                    .selected
+                    .map(|index| index.saturating_sub(1));
+            }
+            context.monitored_messages.entries.push(message);
+            Action::None
+        }
+        AppEvent::FocusMemberSearch => {
+            context.focus = Section::MemberSearch;
+            context
+                .searches
+                .entry(Section::MemberSearch)
+                .or_default()
+                .editing = true;
+            let indexing = context.tasks.iter().any(|task| task.kind == "member index");
+            if context.member_index.entries.is_empty() && !indexing {
+                Action::IndexMembers
+            } else {
+                Action::None
+            }
+        }
+        AppEvent::MembersIndexed(members) => {
+            let selected = context.member_index.selected_entry().cloned();
+            let entries = &mut context.member_index.entries;
+            entries.extend(members);
+            entries.sort_by(|a, b| {
+                (&a.name, &a.service, &a.path, &a.interface, a.kind).cmp(&(
+                    &b.name,
+                    &b.service,
+                    &b.path,
+                    &b.interface,
+                    b.kind,
+                ))
+            });
+            context.member_index.selected = selected
+                .and_then(|selected| entries.iter().position(|entry| *entry == selected))
+                .map(|index| index as u32);
+            Action::None
+        }
+        AppEvent::SelectNextIndexedMember => {
+            let visible = visible_rows(context, &Section::MemberSearch);
+            context.member_index.select_next(&visible);
+            Action::None
+        }
+        AppEvent::SelectPreviousIndexedMember => {
+            let visible = visible_rows(context, &Section::MemberSearch);
+            context.member_index.select_previous(&visible);
+            Action::None
+        }
+        AppEvent::JumpLoaded { member, nodes } => jump_to_member(context, member, nodes),
+        AppEvent::FocusObjectQuery => {
+            // starts out with the interface selected in the interface tree
+            if context.object_query.is_empty() {
+                if let Some(row) = context.members.selected_entry() {
+                    context.object_query = context.interfaces[row.interface()].name.to_owned();
+                }
+            }
+            context.focus = Section::ObjectQuery;
+            Action::None
+        }
+        AppEvent::FocusFoundObjects => {
+            context.focus = Section::FoundObjects;
+            Action::None
+        }
+        AppEvent::ObjectQueryInput(c) => {
+            context.object_query.push(c);
+            Action::None
+        }
+        AppEvent::ObjectQueryDeleted => {
+            context.object_query.pop();
+            Action::None
+        }
+        AppEvent::ObjectQueryStarted { interfaces } => {
+            if interfaces.is_empty() {
+                return Action::None;
+            }
+            abort_tasks(context, &["object query", "comparison"]);
+            let services = services_in_view(context);
+            context.queried_interfaces = interfaces.to_owned();
+            context.queried_services = services.len();
+            context.found_objects.replace(Vec::new());
+            context.focus = Section::FoundObjects;
+            Action::ScanObjects {
+                services,
+                interfaces,
+            }
+        }
+        AppEvent::ObjectsFound(objects) => {
+            let selected = context.found_objects.selected_entry().cloned();
+            let entries = &mut context.found_objects.entries;
+            entries.extend(objects);
+            entries.sort_by(|a, b| (&a.service, &a.path).cmp(&(&b.service, &b.path)));
+            context.found_objects.selected = selected
+                .and_then(|selected| entries.iter().position(|entry| *entry == selected))
+                .map(|index| index as u32);
+            Action::None
+        }
+        AppEvent::SelectNextFoundObject => {
+            let visible: Vec<usize> = (0..context.found_objects.entries.len()).collect();
+            context.found_objects.select_next(&visible);
+            Action::None
+        }
+        AppEvent::SelectPreviousFoundObject => {
+            let visible: Vec<usize> = (0..context.found_objects.entries.len()).collect();
+            context.found_objects.select_previous(&visible);
+            Action::None
+        }
+        AppEvent::ObjectCompared {
+            service,
+            path,
+            properties,
+        } => {
+            let object = context
+                .found_objects
+                .entries
+                .iter_mut()
+                .find(|object| object.service == service && object.path == path);
+            if let Some(object) = object {
+                object.properties = Some(properties);
+            }
+            Action::None
+        }
+        AppEvent::SelectNextMonitoredMessage => {
+            let visible = visible_rows(context, &Section::Monitor);
+            context.monitored_messages.select_next(&visible);
+            Action::None
+        }
+        AppEvent::SelectPreviousMonitoredMessage => {
+            let visible = visible_rows(context, &Section::Monitor);
+            context.monitored_messages.select_previous(&visible);
+            Action::None
+        }
+        AppEvent::FocusMatchRule => {
+            context.match_rule_input = context
+                .match_rule
+                .as_ref()
+                .map(MatchRule::to_input)
+                .unwrap_or_default();
+            context.match_rule_error = None;
+            context.focus = Section::MatchRule;
+            Action::None
+        }
+        AppEvent::MatchRuleInput(c) => {
+            context.match_rule_input.push(c);
+            Action::None
+        }
+        AppEvent::MatchRuleDeleted => {
+            context.match_rule_input.pop();
+            Action::None
+        }
+        AppEvent::SearchStarted => {
+            let focus = context.focus.clone();
+            context.searches.entry(focus).or_default().editing = true;
+            Action::None
+        }
+        AppEvent::SearchInput(c) => {
+            if let Some(search) = context.searches.get_mut(&context.focus) {
+                search.query.push(c);
+            }
+            drop_hidden_selection(context);
+            Action::None
+        }
+        AppEvent::SearchDeleted => {
+            if let Some(search) = context.searches.get_mut(&context.focus) {
+                search.query.pop();
+            }
+            drop_hidden_selection(context);
+            Action::None
+        }
+        AppEvent::SearchModeCycled => {
+            if let Some(search) = context.searches.get_mut(&context.focus) {
+                search.mode = search.mode.next();
+            }
+            drop_hidden_selection(context);
+            Action::None
+        }
+        AppEvent::SearchFinished => {
+            if let Some(search) = context.searches.get_mut(&context.focus) {
+                search.editing = false;
+                if search.query.is_empty() {
+                    context.searches.remove(&context.focus);
+                }
+            }
+            Action::None
+        }
+        AppEvent::SearchCleared => {
+            context.searches.remove(&context.focus);
+            Action::None
+        }
+        AppEvent::SelectNextProperty => {
+            let visible = visible_rows(context, &Section::Properties);
+            context.properties.select_next(&visible);
+            Action::None
+        }
+        AppEvent::SelectPreviousProperty => {
+            let visible = visible_rows(context, &Section::Properties);
+            context.properties.select_previous(&visible);
+            Action::None
+        }
+        AppEvent::Error(e) => {
+            context.error = Some(e);
+            Action::None
+        }
+        AppEvent::ErrorDismissed => {
+            context.error = None;
+            Action::None
+        }
+        AppEvent::FocusBusNames => {
+            context.focus = Section::BusFrame;
+            Action::None
+        }
+        AppEvent::FocusPaths => {
+            context.focus = Section::BusPath;
+            Action::None
+        }
+        AppEvent::FocusMethods => {
+            context.focus = Section::Methods;
+            context.member_view = MemberView::Methods;
+            Action::None
+        }
+        AppEvent::FocusProperties => {
+            context.focus = Section::Properties;
+            context.member_view = MemberView::Properties;
+            Action::None
+        }
+        AppEvent::FocusPropertyValue => {
+            // only writable properties can be edited, the input starts with the current value
+            if let Some(property) = context
+                .properties
+                .selected_entry()
+                .filter(|property| property.is_writable())
+            {
+                context.property_value = property.value.to_owned().unwrap_or_default();
+                context.property_result = None;
+                context.focus = Section::PropertyValue;
+            }
+            Action::None
+        }
+        AppEvent::FocusArguments => {
+            if context.selected_method().is_some() {
+                context.focus = Section::Arguments;
+            }
+            Action::None
+        }
+        AppEvent::ArgumentInput(c) => {
+            context.arguments.push(c);
+            Action::None
+        }
+        AppEvent::ArgumentDeleted => {
+            context.arguments.pop();
+            Action::None
+        }
+        AppEvent::PropertyValueInput(c) => {
+            context.property_value.push(c);
+            Action::None
+        }
+        AppEvent::PropertyValueDeleted => {
+            context.property_value.pop();
+            Action::None
+        }
+    }
+}
 
-fn select_last_bus_name(app: &mut App) -> Action {
-    match app.bus_name_state.selected.as_ref() {
-        Some(index) => select_bus_name(app, *index as i32 - 1),
-        None => select_bus_name(app, 0),
+/// The rows of the interface tree, members of collapsed interfaces are left out.
+fn member_rows(context: &BusContext) -> Vec<MemberRow> {
+    let mut rows = Vec::new();
+    for (index, interface) in context.interfaces.iter().enumerate() {
+        rows.push(MemberRow::Interface(index));
+        if context.is_expanded(interface) {
+            rows.extend(
+                (0..interface.methods.len()).map(|method| MemberRow::Method(index, method)),
+            );
+            rows.extend(
+                (0..interface.signals.len()).map(|signal| MemberRow::Signal(index, signal)),
+            );
+            rows.extend(
+                (0..interface.properties.len())
+                    .map(|property| MemberRow::Property(index, property)),
+            );
+        }
     }
+
+    rows
 }
 
-fn select_bus_name(app: &mut App, index: i32) -> Action {
-    let bus_names: Vec<&String> = filter_bus_names(app).collect();
-    if index < i32::try_from(bus_names.len()).unwrap() && index >= 0 {
-        app.bus_name_state.selected = Some(index as u32);
+/// Lists the rows of the object tree again after paths were expanded, collapsed or loaded, the
+/// selected path stays selected while it's visible.
+fn update_paths(context: &mut BusContext) {
+    let selected = context.paths.selected_entry().cloned();
+    let mut rows = Vec::new();
+    if !context.objects.is_empty() {
+        push_path_rows(context, "/", &mut rows);
     }
-    match app.bus_name_state.selected {
-        Some(index) => Action::LoadPaths {
-            bus_name: app
-                .bus_name_state
-                .entries
-                .get(index as usize)
-                .unwrap()
-                .to_owned(),
+
+    context.paths.selected = selected
+        .and_then(|selected| rows.iter().position(|row| *row == selected))
+        .map(|index| index as u32);
+    context.paths.entries = rows;
+}
+
+fn push_path_rows(context: &BusContext, path: &str, rows: &mut Vec<String>) {
+    rows.push(path.to_string());
+    if !context.expanded_paths.contains(path) {
+        return;
+    }
+    if let Some(node) = context.objects.get(path) {
+        for child in &node.children {
+            push_path_rows(context, child, rows);
+        }
+    }
+}
+
+/// Watches the object managers not watched yet.
+fn watch_object_managers(context: &mut BusContext) -> Action {
+    let paths: Vec<String> = context
+        .objects
+        .values()
+        .filter(|node| node.is_object_manager())
+        .filter(|node| !context.watched_managers.contains(&node.path))
+        .map(|node| node.path.to_owned())
+        .collect();
+
+    match selected_bus_name(context) {
+        Some(bus_name) if !paths.is_empty() => {
+            context.watched_managers.extend(paths.iter().cloned());
+            Action::WatchObjects { bus_name, paths }
+        }
+        _ => Action::None,
+    }
+}
+
+fn stop_object_watches(context: &mut BusContext) {
+    context
+        .object_watches
+        .drain(..)
+        .for_each(|watch| watch.abort());
+    context.watched_managers.clear();
+}
+
+fn load_interfaces(context: &BusContext, changed: bool) -> Action {
+    match (
+        changed,
+        selected_bus_name(context),
+        context.paths.selected_entry(),
+    ) {
+        (true, Some(bus_name), Some(path)) => Action::LoadInterfaces {
+            bus_name,
+            path: path.to_owned(),
         },
+        _ => Action::None,
+    }
+}
+
+pub fn selected_bus_name(context: &BusContext) -> Option<String> {
+    context
+        .bus_name_state
+        .selected
+        .and_then(|index| filter_bus_names(context).nth(index as usize))
+        .cloned()
+}
+
+fn select_next_bus_name(context: &mut BusContext) -> Action {
+    let visible = visible_rows(context, &Section::BusFrame);
+    let next = next_visible(context.bus_name_state.selected, &visible);
+    select_bus_name(context, next.map_or(-1, |index| index as i32))
+}
+
+fn select_last_bus_name(context: &mut BusContext) -> Action {
+    let visible = visible_rows(context, &Section::BusFrame);
+    let previous = previous_visible(context.bus_name_state.selected, &visible);
+    select_bus_name(context, previous.map_or(-1, |index| index as i32))
+}
+
+/// Drops the selection of the focused pane once its search hides the selected row.
+fn drop_hidden_selection(context: &mut BusContext) {
+    let visible = visible_rows(context, &context.focus);
+    let selected = match context.focus {
+        Section::BusFrame => &mut context.bus_name_state.selected,
+        Section::BusPath => &mut context.paths.selected,
+        Section::Methods => &mut context.members.selected,
+        Section::Properties => &mut context.properties.selected,
+        Section::Signals => &mut context.signals.selected,
+        Section::Monitor => &mut context.monitored_messages.selected,
+        Section::MemberSearch => &mut context.member_index.selected,
+        _ => return,
+    };
+    if selected.is_some_and(|index| !visible.contains(&(index as usize))) {
+        *selected = None;
+    }
+}
+
+fn sort_bus_names(context: &mut BusContext) {
+    let details = &context.name_details;
+    let detail = |name: &String| details.get(name);
+    let by_detail = |a: &String, b: &String| match context.name_sort {
+        NameSort::Name => cmp::Ordering::Equal,
+        NameSort::Pid => compare_known(
+            detail(a).and_then(|details| details.pid),
+            detail(b).and_then(|details| details.pid),
+        ),
+        NameSort::Uid => compare_known(
+            detail(a).and_then(|details| details.uid),
+            detail(b).and_then(|details| details.uid),
+        ),
+        NameSort::Command => compare_known(
+            detail(a).and_then(|details| details.cmdline.as_ref()),
+            detail(b).and_then(|details| details.cmdline.as_ref()),
+        ),
+    };
+
+    let mut entries = std::mem::take(&mut context.bus_name_state.entries);
+    entries.sort_by(|a, b| by_detail(a, b).then_with(|| a.cmp(b)));
+    context.bus_name_state.entries = entries;
+}
+
+/// Orders missing values last.
+fn compare_known<T: Ord>(a: Option<T>, b: Option<T>) -> cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => cmp::Ordering::Less,
+        (None, Some(_)) => cmp::Ordering::Greater,
+        (None, None) => cmp::Ordering::Equal,
+    }
+}
+
+/// Selects `name` again after the list changed, nothing if it's gone.
+/// Identifies a row of the bus name list across changes of the list, a name is listed once
+/// more below each connection queued for it when grouped.
+struct SelectedName {
+    name: String,
+    /// The connection the row is listed below, if grouped.
+    group: Option<String>,
+}
+
+fn selected_name_row(context: &BusContext) -> Option<SelectedName> {
+    let index = context.bus_name_state.selected? as usize;
+    name_rows(context).get(index).map(|row| SelectedName {
+        name: row.name.to_owned(),
+        group: row.group.cloned(),
+    })
+}
+
+/// Selects the same row again, or the row of its name not queued if the row is gone.
+fn reselect_bus_name(context: &mut BusContext, selected: Option<SelectedName>) {
+    context.bus_name_state.selected = selected
+        .and_then(|selected| {
+            let rows = name_rows(context);
+            rows.iter()
+                .position(|row| *row.name == selected.name && row.group == selected.group.as_ref())
+                .or_else(|| {
+                    rows.iter()
+                        .position(|row| *row.name == selected.name && !row.queued)
+                })
+        })
+        .map(|index| index as u32);
+}
+
+fn select_bus_name(context: &mut BusContext, index: i32) -> Action {
+    let bus_names: Vec<&String> = filter_bus_names(context).collect();
+    if index < i32::try_from(bus_names.len()).unwrap() && index >= 0 {
+        context.bus_name_state.selected = Some(index as u32);
+    }
+    match selected_bus_name(context) {
+        // browsing would start the service, that's left to `ActivateBusName`
+        Some(bus_name) if context.inactive.contains(&bus_name) => {
+            abort_tasks(context, &["paths", "children"]);
+            reduce_event(context, AppEvent::PathsLoaded(Vec::new()))
+        }
+        Some(bus_name) => Action::LoadPaths { bus_name },
         None => Action::None,
     }
 }
+
+/// The running names the bus name list shows, as far as its search and filters leave them.
+fn services_in_view(context: &BusContext) -> Vec<String> {
+    let rows = name_rows(context);
+    let mut services: Vec<String> = Vec::new();
+    for index in visible_rows(context, &Section::BusFrame) {
+        let name = rows[index].name;
+        // grouped, a name is listed below its owner and the connections queued for it
+        if !context.inactive.contains(name) && !services.contains(name) {
+            services.push(name.to_owned());
+        }
+    }
+    services
+}
+
+/// The running names, a connection owning a well-known name is only indexed under that name.
+fn services_to_index(context: &BusContext) -> Vec<String> {
+    let owners: HashSet<&String> = context
+        .name_details
+        .values()
+        .map(|details| &details.owner)
+        .collect();
+    context
+        .bus_name_state
+        .entries
+        .iter()
+        .filter(|name| !context.inactive.contains(*name))
+        .filter(|name| !(name.starts_with(':') && owners.contains(name)))
+        .cloned()
+        .collect()
+}
+
+/// Selects the service and path of `member` with the objects up to it loaded, its interface
+/// follows with `InterfacesLoaded`. Searches and filters hiding them are dropped.
+fn jump_to_member(
+    context: &mut BusContext,
+    member: IndexedMember,
+    nodes: Vec<ObjectNode>,
+) -> Action {
+    for section in [Section::BusFrame, Section::BusPath, Section::Methods] {
+        context.searches.remove(&section);
+    }
+    if member.service.starts_with(':') {
+        context.hide_unique_names = false;
+    }
+    let selected = SelectedName {
+        name: member.service.to_owned(),
+        group: None,
+    };
+    reselect_bus_name(context, Some(selected));
+
+    let watch = reduce_event(context, AppEvent::PathsLoaded(nodes));
+    let mut parent = parent_path(&member.path);
+    while let Some(path) = parent {
+        context.expanded_paths.insert(path.to_string());
+        parent = parent_path(path);
+    }
+    update_paths(context);
+    context.paths.selected = context
+        .paths
+        .entries
+        .iter()
+        .position(|path| *path == member.path)
+        .map(|index| index as u32);
+    context.focus = Section::Methods;
+
+    let load = Action::LoadInterfaces {
+        bus_name: member.service.to_owned(),
+        path: member.path.to_owned(),
+    };
+    context.jump = Some(member);
+    Action::Batch(vec![watch, load])
+}
+
+/// Whether `row` of the interface tree is `member`.
+fn is_member(context: &BusContext, row: &MemberRow, member: &IndexedMember) -> bool {
+    let interface = &context.interfaces[row.interface()];
+    if interface.name != member.interface {
+        return false;
+    }
+    match (*row, member.kind) {
+        (MemberRow::Interface(_), MemberKind::Interface) => true,
+        (MemberRow::Method(_, index), MemberKind::Method) => {
+            interface.methods[index].name == member.name
+        }
+        (MemberRow::Signal(_, index), MemberKind::Signal) => {
+            interface.signals[index].name == member.name
+        }
+        (MemberRow::Property(_, index), MemberKind::Property) => {
+            interface.properties[index].name == member.name
+        }
+        _ => false,
+    }
+}
+
+fn abort_tasks(context: &mut BusContext, kinds: &[&str]) {
+    context.tasks.retain(|task| {
+        let aborted = kinds.contains(&task.kind);
+        if aborted {
+            task.abort.abort();
+        }
+        !aborted
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::SystemTime;
+
+    use futures::future::{self, AbortHandle, Abortable};
+
+    use crate::{
+        dbus::{
+            IndexedMember, Interface, MemberKind, Method, NameDetails, NameOwnerChange,
+            ObjectChange, ObjectNode,
+        },
+        signals::Signal,
+    };
+
+    use super::{
+        name_rows, selected_bus_name, spawn_for, Action, App, AppEvent, FoundObject, MemberRow,
+        NameChange, NameSort, Section, MAX_LOG_ENTRIES,
+    };
+
+    #[test]
+    fn test_tabs_keep_their_state() {
+        let mut app = App::default();
+        app.reduce(Action::InputArgument('1'));
+
+        app.reduce(Action::NewTab);
+        assert_eq!(app.contexts.len(), 2);
+        assert_eq!(app.active, 1);
+        assert_eq!(app.context().focus, Section::BusAddress);
+        assert_eq!(app.context().bus_input, "session");
+        app.reduce(Action::InputArgument('2'));
+
+        app.reduce(Action::NextTab);
+        assert_eq!(app.active, 0);
+        assert_eq!(app.context().arguments, "1");
+
+        app.reduce(Action::PreviousTab);
+        app.reduce(Action::CloseTab);
+        assert_eq!(app.contexts.len(), 1);
+        assert_eq!(app.context().arguments, "1");
+
+        app.reduce(Action::CloseTab);
+        assert_eq!(app.contexts.len(), 1);
+    }
+
+    #[test]
+    fn test_cancelled_task_is_ignored() {
+        let mut app = App::default();
+        app.reduce(Action::Connect {
+            address: String::from("session"),
+        });
+        assert_eq!(app.context().tasks.len(), 1);
+        let id = app.context().tasks[0].id;
+
+        app.reduce(Action::CancelTasks);
+        assert!(app.context().tasks.is_empty());
+
+        app.handle(
+            0,
+            AppEvent::TaskFinished {
+                id,
+                event: Box::new(AppEvent::BusNamesLoaded(vec![String::from("org.foo")])),
+            },
+        );
+        assert!(app.context().bus_name_state.entries.is_empty());
+    }
+
+    #[test]
+    fn test_tasks_replaced_by_key() {
+        let mut app = App::default();
+        for path in ["/a", "/b", "/a"] {
+            let key = Some(String::from(path));
+            let started = spawn_for(app.context(), "children", key, async { AppEvent::None });
+            app.handle(0, started);
+        }
+
+        let keys: Vec<Option<&str>> = app
+            .context()
+            .tasks
+            .iter()
+            .map(|task| task.key.as_deref())
+            .collect();
+        assert_eq!(keys, [Some("/b"), Some("/a")]);
+    }
+
+    #[test]
+    fn test_bus_monitor_stopped_with_tab() {
+        let mut app = App::default();
+        app.reduce(Action::NewTab);
+        let (monitor, registration) = AbortHandle::new_pair();
+        let reader = Abortable::new(future::pending::<()>(), registration);
+        app.handle(1, AppEvent::BusMonitorStarted(monitor));
+        assert!(app.context().is_monitoring());
+
+        app.reduce(Action::CloseTab);
+        assert!(reader.is_aborted());
+    }
+
+    #[test]
+    fn test_error_until_dismissed() {
+        let mut app = App::default();
+        app.reduce(Action::LoadBusNames);
+        assert!(app.context().error.is_some());
+
+        app.reduce(Action::DismissError);
+        assert!(app.context().error.is_none());
+    }
+
+    fn interface(name: &str) -> Interface {
+        Interface {
+            name: name.to_string(),
+            methods: vec![Method {
+                interface: name.to_string(),
+                name: String::from("Ping"),
+                in_signature: String::new(),
+                out_signature: String::new(),
+                in_args: Vec::new(),
+                out_args: Vec::new(),
+                annotations: Vec::new(),
+            }],
+            signals: Vec::new(),
+            properties: Vec::new(),
+            annotations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_interface_tree() {
+        let mut app = App::default();
+        app.handle(
+            0,
+            AppEvent::InterfacesLoaded(vec![
+                interface("org.freedesktop.DBus.Peer"),
+                interface("org.foo"),
+            ]),
+        );
+        // standard interfaces start collapsed
+        assert_eq!(
+            app.context().members.entries,
+            vec![
+                MemberRow::Interface(0),
+                MemberRow::Interface(1),
+                MemberRow::Method(1, 0)
+            ]
+        );
+
+        app.reduce(Action::SelectNextMember);
+        app.reduce(Action::ToggleInterface);
+        assert_eq!(app.context().members.entries.len(), 4);
+        assert_eq!(app.context().members.selected, Some(0));
+
+        app.reduce(Action::SelectNextMember);
+        assert_eq!(
+            app.context().selected_method().unwrap().interface,
+            "org.freedesktop.DBus.Peer"
+        );
+        app.reduce(Action::CollapseInterface);
+        assert_eq!(app.context().members.entries.len(), 3);
+        assert_eq!(app.context().members.selected, Some(0));
+    }
+
+    fn node(path: &str, children: &[&str]) -> ObjectNode {
+        ObjectNode {
+            path: path.to_string(),
+            interfaces: Vec::new(),
+            children: children.iter().map(|child| child.to_string()).collect(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_path_tree() {
+        let mut app = App::default();
+        app.handle(
+            0,
+            AppEvent::PathsLoaded(vec![node("/", &["/org"]), node("/org", &["/org/a"])]),
+        );
+        assert_eq!(app.context().paths.entries, vec!["/", "/org"]);
+
+        app.reduce(Action::SelectNextPath);
+        app.reduce(Action::SelectNextPath);
+        app.handle(
+            0,
+            AppEvent::ChildrenLoaded {
+                path: String::from("/org"),
+                nodes: vec![node("/org", &["/org/a"]), node("/org/a", &[])],
+                recursive: false,
+            },
+        );
+        assert_eq!(app.context().paths.entries, vec!["/", "/org", "/org/a"]);
+        assert_eq!(app.context().paths.selected, Some(1));
+
+        app.reduce(Action::CollapsePath);
+        assert_eq!(app.context().paths.entries, vec!["/", "/org"]);
+        app.reduce(Action::CollapsePath);
+        assert_eq!(app.context().paths.selected, Some(0));
+
+        // the children are known by now, expanding again needs no introspection
+        app.reduce(Action::SelectNextPath);
+        app.reduce(Action::ExpandPath { recursive: false });
+        assert_eq!(app.context().paths.entries.len(), 3);
+    }
+
+    #[test]
+    fn test_nodes_discovered() {
+        let mut app = App::default();
+        app.handle(0, AppEvent::BusNamesLoaded(vec![String::from("org.a")]));
+        app.contexts[0].bus_name_state.selected = Some(0);
+        app.handle(0, AppEvent::PathsLoaded(vec![node("/", &["/org"])]));
+        let discovered = |bus_name: &str, nodes| AppEvent::NodesDiscovered {
+            bus_name: bus_name.to_string(),
+            nodes,
+            recursive: true,
+        };
+
+        app.handle(0, discovered("org.b", vec![node("/org", &["/org/a"])]));
+        assert_eq!(app.context().discovered, 0);
+
+        app.handle(0, discovered("org.a", vec![node("/org", &["/org/a"])]));
+        app.handle(0, discovered("org.a", vec![node("/org/a", &[])]));
+        assert_eq!(app.context().discovered, 2);
+        assert_eq!(app.context().paths.entries, vec!["/", "/org", "/org/a"]);
+    }
+
+    #[test]
+    fn test_live_bus_names() {
+        let mut app = App::default();
+        let names = ["org.a", "org.b"].map(String::from);
+        app.handle(0, AppEvent::BusNamesLoaded(names.to_vec()));
+        app.contexts[0].bus_name_state.selected = Some(1);
+        let changed = |name: &str, old_owner: Option<&str>, new_owner: Option<&str>| {
+            AppEvent::NameOwnerChanged(NameOwnerChange {
+                name: name.to_string(),
+                old_owner: old_owner.map(String::from),
+                new_owner: new_owner.map(String::from),
+            })
+        };
+
+        app.handle(0, changed("org.c", None, Some(":1.3")));
+        app.handle(0, changed("org.a", Some(":1.1"), None));
+        assert_eq!(
+            app.context().bus_name_state.entries,
+            vec!["org.a", "org.b", "org.c"]
+        );
+        assert_eq!(app.context().name_changes["org.c"].0, NameChange::Appeared);
+
+        let (_, at) = app.context().name_changes["org.a"];
+        app.handle(
+            0,
+            AppEvent::NameChangeExpired {
+                name: String::from("org.a"),
+                at,
+            },
+        );
+        assert_eq!(app.context().bus_name_state.entries, vec!["org.b", "org.c"]);
+        // still the same name selected
+        assert_eq!(app.context().bus_name_state.selected, Some(0));
+        assert!(!app.context().name_changes.contains_key("org.a"));
+    }
+
+    #[test]
+    fn test_changes_before_names_loaded() {
+        let mut app = App::default();
+        for (name, old_owner, new_owner) in
+            [("org.c", None, Some(":1.3")), ("org.a", Some(":1.1"), None)]
+        {
+            app.handle(
+                0,
+                AppEvent::NameOwnerChanged(NameOwnerChange {
+                    name: String::from(name),
+                    old_owner: old_owner.map(String::from),
+                    new_owner: new_owner.map(String::from),
+                }),
+            );
+        }
+
+        // listed after both changes, `org.a` is gone already
+        let names = ["org.b", "org.c"].map(String::from);
+        app.handle(0, AppEvent::BusNamesLoaded(names.to_vec()));
+        assert_eq!(
+            app.context().bus_name_state.entries,
+            vec!["org.a", "org.b", "org.c"]
+        );
+        assert_eq!(app.context().name_changes["org.c"].0, NameChange::Appeared);
+
+        let (_, at) = app.context().name_changes["org.a"];
+        app.handle(
+            0,
+            AppEvent::NameChangeExpired {
+                name: String::from("org.a"),
+                at,
+            },
+        );
+        assert_eq!(app.context().bus_name_state.entries, vec!["org.b", "org.c"]);
+    }
+
+    #[test]
+    fn test_activatable_names() {
+        let mut app = App::default();
+        app.handle(0, AppEvent::BusNamesLoaded(vec![String::from("org.a")]));
+        app.handle(
+            0,
+            AppEvent::ActivatableNamesLoaded(vec![String::from("org.b"), String::from("org.a")]),
+        );
+        assert_eq!(app.context().bus_name_state.entries, vec!["org.a", "org.b"]);
+        assert!(app.context().inactive.contains("org.b"));
+
+        // selecting it doesn't start it
+        app.contexts[0].bus_name_state.selected = Some(0);
+        app.reduce(Action::SelectNextBusName);
+        assert!(app.context().tasks.is_empty());
+        assert!(matches!(
+            app.context().activate_selected_name(),
+            Action::ActivateBusName { .. }
+        ));
+
+        app.handle(0, AppEvent::ServiceStarted(String::from("org.b")));
+        assert!(app.context().inactive.is_empty());
+        // listing the names again keeps it
+        app.handle(0, AppEvent::BusNamesLoaded(vec![String::from("org.a")]));
+        assert_eq!(app.context().bus_name_state.entries, vec!["org.a", "org.b"]);
+        assert_eq!(app.context().bus_name_state.selected, Some(1));
+    }
+
+    #[test]
+    fn test_keep_queued_row_selected() {
+        let mut app = App::default();
+        let names = [":1.1", ":1.2", "org.a"].map(String::from);
+        app.handle(0, AppEvent::BusNamesLoaded(names.to_vec()));
+        app.handle(
+            0,
+            AppEvent::NameDetailsLoaded {
+                name: String::from("org.a"),
+                details: NameDetails {
+                    owner: String::from(":1.1"),
+                    queued: vec![String::from(":1.2")],
+                    ..NameDetails::default()
+                },
+            },
+        );
+        app.handle(0, AppEvent::NameGroupsToggled);
+        // `org.a` queued by `:1.2`
+        app.contexts[0].bus_name_state.selected = Some(3);
+
+        app.handle(
+            0,
+            AppEvent::NameOwnerChanged(NameOwnerChange {
+                name: String::from(":1.0"),
+                old_owner: None,
+                new_owner: Some(String::from(":1.0")),
+            }),
+        );
+        let rows = name_rows(app.context());
+        let selected = rows[app.context().bus_name_state.selected.unwrap() as usize].clone();
+        assert_eq!(selected.name, "org.a");
+        assert!(selected.queued);
+        assert_eq!(selected.group.map(String::as_str), Some(":1.2"));
+    }
+
+    #[test]
+    fn test_sort_by_details() {
+        let mut app = App::default();
+        let names = ["org.c", "org.a", "org.b"].map(String::from);
+        app.handle(0, AppEvent::BusNamesLoaded(names.to_vec()));
+        assert_eq!(
+            app.context().bus_name_state.entries,
+            vec!["org.a", "org.b", "org.c"]
+        );
+        app.contexts[0].bus_name_state.selected = Some(0);
+
+        for (name, pid) in [("org.a", 30), ("org.c", 10)] {
+            app.handle(
+                0,
+                AppEvent::NameDetailsLoaded {
+                    name: name.to_string(),
+                    details: NameDetails {
+                        pid: Some(pid),
+                        ..NameDetails::default()
+                    },
+                },
+            );
+        }
+        app.reduce(Action::SortBusNames);
+
+        assert_eq!(app.context().name_sort, NameSort::Pid);
+        // without a pid it comes last
+        assert_eq!(
+            app.context().bus_name_state.entries,
+            vec!["org.c", "org.a", "org.b"]
+        );
+        assert_eq!(app.context().bus_name_state.selected, Some(1));
+    }
+
+    #[test]
+    fn test_objects_changed() {
+        let mut app = App::default();
+        app.handle(0, AppEvent::BusNamesLoaded(vec![String::from("org.a")]));
+        app.contexts[0].bus_name_state.selected = Some(0);
+        app.handle(0, AppEvent::PathsLoaded(vec![node("/", &[])]));
+        let changed = |change| AppEvent::ObjectsChanged {
+            bus_name: String::from("org.a"),
+            change,
+        };
+        let interfaces = vec![String::from("org.a.Device")];
+
+        app.handle(
+            0,
+            changed(ObjectChange::InterfacesAdded {
+                path: String::from("/a"),
+                interfaces: interfaces.clone(),
+            }),
+        );
+        assert_eq!(app.context().paths.entries, vec!["/", "/a"]);
+        assert!(app.context().objects["/a"].has_interfaces());
+
+        app.handle(
+            0,
+            changed(ObjectChange::InterfacesRemoved {
+                path: String::from("/a"),
+                interfaces,
+            }),
+        );
+        assert_eq!(app.context().paths.entries, vec!["/"]);
+    }
+
+    #[test]
+    fn test_log_capped() {
+        let mut app = App::default();
+        for _ in 0..MAX_LOG_ENTRIES + 10 {
+            app.handle(0, AppEvent::FocusBusAddress);
+        }
+
+        assert_eq!(app.context().log.entries.len(), MAX_LOG_ENTRIES);
+    }
+
+    #[test]
+    fn test_search_signals() {
+        let mut app = App::default();
+        for member in ["Changed", "Removed", "Added"] {
+            app.handle(
+                0,
+                AppEvent::SignalReceived(Signal {
+                    received: SystemTime::UNIX_EPOCH,
+                    sender: String::from(":1.1"),
+                    sender_name: None,
+                    path: String::from("/"),
+                    interface: String::from("org.test"),
+                    member: member.to_string(),
+                    body: String::new(),
+                }),
+            );
+        }
+        app.reduce(Action::FocusSignals);
+        app.reduce(Action::SelectNextSignal);
+        assert_eq!(app.context().signals.selected, Some(0));
+
+        app.reduce(Action::Search);
+        assert!(app.context().is_searching());
+        app.reduce(Action::InputSearch('a'));
+        app.reduce(Action::InputSearch('d'));
+        // only `Added` contains "ad", the selected signal is hidden
+        assert_eq!(app.context().signals.selected, None);
+
+        app.reduce(Action::CycleSearchMode);
+        app.reduce(Action::FinishSearch);
+        assert!(!app.context().is_searching());
+        // "ad" in order, `Removed` has no "a"
+        app.reduce(Action::SelectNextSignal);
+        app.reduce(Action::SelectNextSignal);
+        assert_eq!(app.context().signals.selected, Some(2));
+        app.reduce(Action::SelectLastSignal);
+        assert_eq!(app.context().signals.selected, Some(0));
+
+        app.reduce(Action::Search);
+        app.reduce(Action::ClearSearch);
+        assert!(app.context().searches.is_empty());
+        app.reduce(Action::SelectNextSignal);
+        assert_eq!(app.context().signals.selected, Some(1));
+    }
+
+    #[test]
+    fn test_jump_to_member() {
+        let mut app = App::default();
+        let names = [":1.5", "org.a"].map(String::from);
+        app.handle(0, AppEvent::BusNamesLoaded(names.to_vec()));
+        let indexed = |service: &str, name: &str| IndexedMember {
+            service: service.to_string(),
+            path: String::from("/org/a/b"),
+            interface: String::from("org.freedesktop.DBus.Peer"),
+            kind: MemberKind::Method,
+            name: name.to_string(),
+        };
+        app.handle(0, AppEvent::MembersIndexed(vec![indexed("org.a", "Ping")]));
+        app.handle(
+            0,
+            AppEvent::MembersIndexed(vec![indexed(":1.5", "Ping"), indexed(":1.5", "Echo")]),
+        );
+        let found: Vec<(&str, &str)> = app
+            .context()
+            .member_index
+            .entries
+            .iter()
+            .map(|member| (member.name.as_str(), member.service.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("Echo", ":1.5"), ("Ping", ":1.5"), ("Ping", "org.a")]
+        );
+
+        let member = app.context().member_index.entries[1].clone();
+        app.handle(
+            0,
+            AppEvent::JumpLoaded {
+                member,
+                nodes: vec![
+                    node("/", &["/org"]),
+                    node("/org", &["/org/a"]),
+                    node("/org/a", &["/org/a/b"]),
+                    node("/org/a/b", &[]),
+                ],
+            },
+        );
+        // unique names are hidden until one is jumped to
+        assert_eq!(selected_bus_name(app.context()).unwrap(), ":1.5");
+        assert_eq!(app.context().paths.selected_entry().unwrap(), "/org/a/b");
+        assert_eq!(app.context().focus, Section::Methods);
+
+        app.handle(
+            0,
+            AppEvent::InterfacesLoaded(vec![interface("org.freedesktop.DBus.Peer")]),
+        );
+        // the standard interface is expanded to show the method
+        assert_eq!(app.context().members.selected, Some(1));
+    }
+
+    #[test]
+    fn test_object_query() {
+        let mut app = App::default();
+        let names = [":1.1", "org.a", "org.b", "org.c"].map(String::from);
+        app.handle(0, AppEvent::BusNamesLoaded(names.to_vec()));
+        app.handle(
+            0,
+            AppEvent::ActivatableNamesLoaded(vec![String::from("org.inactive")]),
+        );
+        // only the running names the bus name search leaves are looked through
+        app.reduce(Action::Search);
+        app.reduce(Action::InputSearch('a'));
+        app.reduce(Action::FinishSearch);
+
+        app.reduce(Action::FocusObjectQuery);
+        for c in "org.x, org.y".chars() {
+            app.reduce(Action::InputObjectQuery(c));
+        }
+        let find = app.context().find_objects();
+        app.reduce(find);
+        assert_eq!(app.context().queried_interfaces, vec!["org.x", "org.y"]);
+        assert_eq!(app.context().queried_services, 1);
+        assert_eq!(app.context().focus, Section::FoundObjects);
+
+        let found = |service: &str, path: &str| FoundObject {
+            service: service.to_string(),
+            path: path.to_string(),
+            properties: None,
+        };
+        app.handle(0, AppEvent::ObjectsFound(vec![found("org.b", "/b")]));
+        app.handle(
+            0,
+            AppEvent::ObjectsFound(vec![found("org.a", "/a/2"), found("org.a", "/a/1")]),
+        );
+        let paths: Vec<&str> = app
+            .context()
+            .found_objects
+            .entries
+            .iter()
+            .map(|object| object.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["/a/1", "/a/2", "/b"]);
+
+        app.handle(
+            0,
+            AppEvent::ObjectCompared {
+                service: String::from("org.a"),
+                path: String::from("/a/2"),
+                properties: Ok(Vec::new()),
+            },
+        );
+        assert_eq!(
+            app.context().found_objects.entries[1].properties,
+            Some(Ok(Vec::new()))
+        );
+
+        app.reduce(Action::SelectNextFoundObject);
+        match app.context().show_selected_object() {
+            Action::JumpToMember(member) => {
+                assert_eq!(
+                    (member.service.as_str(), member.path.as_str()),
+                    ("org.a", "/a/1")
+                );
+                assert_eq!(member.interface, "org.x");
+            }
+            action => panic!("unexpected {:?}", action),
+        }
+    }
+}
@@ -0,0 +1,565 @@
+use std::{
+    convert::TryFrom,
+    fmt::Display,
+    num::{IntErrorKind, ParseIntError},
+};
+
+use zvariant::{Array, Dict, ObjectPath, Signature, Str, Structure, StructureBuilder, Value};
+
+use crate::{
+    error::{DBusConsoleError, DBusConsoleResult},
+    parser::{parse, Expr, ExprKind},
+    tokenizer::{Span, Tokenizer},
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConversionError {
+    pub kind: ConversionErrorKind,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConversionErrorKind {
+    /// The value can't be converted to the D-Bus type `expected` at all.
+    TypeMismatch {
+        expected: String,
+    },
+    /// The number doesn't fit into the D-Bus type `expected`.
+    OutOfRange {
+        expected: String,
+    },
+    /// A negative number for the unsigned D-Bus type `expected`.
+    NegativeUnsigned {
+        expected: String,
+    },
+    InvalidNumber,
+    InvalidObjectPath,
+    InvalidSignature,
+    FieldCount {
+        expected: usize,
+        found: usize,
+    },
+    UnsupportedType {
+        signature: String,
+    },
+    /// The type of a variant can't be inferred from the value, e.g. `()` or mixed dict keys.
+    AmbiguousVariant,
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ConversionErrorKind::TypeMismatch { expected } => {
+                write!(f, "Expected a value of type \"{}\"", expected)
+            }
+            ConversionErrorKind::OutOfRange { expected } => {
+                write!(f, "Number out of range for type \"{}\"", expected)
+            }
+            ConversionErrorKind::NegativeUnsigned { expected } => {
+                write!(f, "Negative number for unsigned type \"{}\"", expected)
+            }
+            ConversionErrorKind::InvalidNumber => write!(f, "Invalid number"),
+            ConversionErrorKind::InvalidObjectPath => write!(f, "Invalid object path"),
+            ConversionErrorKind::InvalidSignature => write!(f, "Invalid signature"),
+            ConversionErrorKind::FieldCount { expected, found } => {
+                write!(f, "Expected {} values, got {}", expected, found)
+            }
+            ConversionErrorKind::UnsupportedType { signature } => {
+                write!(f, "Type \"{}\" is not supported", signature)
+            }
+            ConversionErrorKind::AmbiguousVariant => {
+                write!(f, "Cannot infer the type of this variant")
+            }
+        }
+    }
+}
+
+/// Parses method arguments typed in the console, e.g. `("str", 1, [1, 2], {"k": "v"})`,
+/// into a structure whose fields match the complete types of `signature`.
+pub fn parse_arguments(signature: &str, input: &str) -> DBusConsoleResult<Structure<'static>> {
+    let types = split_signature(signature)?;
+
+    if input.trim().is_empty() {
+        let span = Span {
+            start: 0,
+            end: input.len(),
+        };
+        return Ok(to_structure(&types, &[], &span)?);
+    }
+
+    let expr = parse(Tokenizer::new().tokenize(input)?)?;
+
+    match &expr.kind {
+        ExprKind::Struct(fields) => Ok(to_structure(&types, fields, &expr.span)?),
+        _ => Err(ConversionError {
+            kind: ConversionErrorKind::TypeMismatch {
+                expected: format!("({})", signature),
+            },
+            span: expr.span,
+        }
+        .into()),
+    }
+}
+
+/// Parses a single value typed in the console, e.g. a new property value, as the complete
+/// type `signature`.
+pub fn parse_value(signature: &str, input: &str) -> DBusConsoleResult<Value<'static>> {
+    if split_signature(signature)?.len() != 1 {
+        return Err(DBusConsoleError::InvalidSignature(signature.to_string()));
+    }
+
+    let expr = parse(Tokenizer::new().tokenize(input)?)?;
+
+    Ok(to_value(signature, &expr)?)
+}
+
+/// Splits `signature` into its complete types, e.g. `sa{sv}(ii)` into `s`, `a{sv}` and `(ii)`.
+pub fn split_signature(signature: &str) -> DBusConsoleResult<Vec<&str>> {
+    Signature::try_from(signature)
+        .map_err(|_| DBusConsoleError::InvalidSignature(signature.to_string()))?;
+
+    let mut types = Vec::new();
+    let mut rest = signature;
+
+    while !rest.is_empty() {
+        let len = complete_type_len(rest)
+            .ok_or_else(|| DBusConsoleError::InvalidSignature(signature.to_string()))?;
+        types.push(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    Ok(types)
+}
+
+fn complete_type_len(signature: &str) -> Option<usize> {
+    match signature.chars().next()? {
+        'a' => complete_type_len(&signature[1..]).map(|len| len + 1),
+        '(' | '{' => {
+            let mut depth = 0;
+            signature.char_indices().find_map(|(index, c)| {
+                match c {
+                    '(' | '{' => depth += 1,
+                    ')' | '}' => depth -= 1,
+                    _ => {}
+                }
+                (depth == 0).then(|| index + 1)
+            })
+        }
+        _ => Some(1),
+    }
+}
+
+fn to_structure(
+    types: &[&str],
+    fields: &[Expr],
+    span: &Span,
+) -> Result<Structure<'static>, ConversionError> {
+    if fields.len() != types.len() {
+        return Err(ConversionError {
+            kind: ConversionErrorKind::FieldCount {
+                expected: types.len(),
+                found: fields.len(),
+            },
+            span: span.clone(),
+        });
+    }
+
+    types
+        .iter()
+        .zip(fields)
+        .try_fold(StructureBuilder::new(), |builder, (signature, field)| {
+            Ok(builder.append_field(to_value(signature, field)?))
+        })
+        .map(StructureBuilder::build)
+}
+
+/// Converts `expr` into a value of the complete type `signature`.
+pub fn to_value(signature: &str, expr: &Expr) -> Result<Value<'static>, ConversionError> {
+    let mismatch = || ConversionError {
+        kind: ConversionErrorKind::TypeMismatch {
+            expected: signature.to_string(),
+        },
+        span: expr.span.clone(),
+    };
+
+    match (signature.as_bytes()[0], &expr.kind) {
+        (b'y', ExprKind::Number(number)) => to_integer(signature, number, expr).map(Value::U8),
+        (b'n', ExprKind::Number(number)) => to_integer(signature, number, expr).map(Value::I16),
+        (b'q', ExprKind::Number(number)) => to_integer(signature, number, expr).map(Value::U16),
+        (b'i', ExprKind::Number(number)) => to_integer(signature, number, expr).map(Value::I32),
+        (b'u', ExprKind::Number(number)) => to_integer(signature, number, expr).map(Value::U32),
+        (b'x', ExprKind::Number(number)) => to_integer(signature, number, expr).map(Value::I64),
+        (b't', ExprKind::Number(number)) => to_integer(signature, number, expr).map(Value::U64),
+        (b'd', ExprKind::Number(number)) => {
+            parse_float(number)
+                .map(Value::F64)
+                .ok_or_else(|| ConversionError {
+                    kind: ConversionErrorKind::InvalidNumber,
+                    span: expr.span.clone(),
+                })
+        }
+        (b'b', ExprKind::Boolean(boolean)) => Ok(Value::Bool(*boolean)),
+        (b's', ExprKind::String(string)) => Ok(Value::Str(Str::from(string.to_string()))),
+        (b'o', ExprKind::String(string)) => ObjectPath::try_from(string.to_string())
+            .map(Value::ObjectPath)
+            .map_err(|_| ConversionError {
+                kind: ConversionErrorKind::InvalidObjectPath,
+                span: expr.span.clone(),
+            }),
+        (b'g', ExprKind::String(string)) => Signature::try_from(string.to_string())
+            .map(Value::Signature)
+            .map_err(|_| ConversionError {
+                kind: ConversionErrorKind::InvalidSignature,
+                span: expr.span.clone(),
+            }),
+        (b'v', _) => {
+            let inferred = infer_signature(expr)?;
+            to_value(&inferred, expr).map(|value| Value::Value(Box::new(value)))
+        }
+        (b'a', ExprKind::Dict(entries)) if signature.as_bytes()[1] == b'{' => {
+            to_dict(signature, entries)
+        }
+        (b'a', ExprKind::Array(elements)) if signature.as_bytes()[1] != b'{' => {
+            let element_signature = &signature[1..];
+            let mut array = Array::new(Signature::from_string_unchecked(
+                element_signature.to_string(),
+            ));
+            for element in elements {
+                array
+                    .append(to_value(element_signature, element)?)
+                    .map_err(|_| mismatch())?;
+            }
+            Ok(Value::Array(array))
+        }
+        (b'(', ExprKind::Struct(fields)) => {
+            let types = split_signature(&signature[1..signature.len() - 1]).map_err(|_| {
+                ConversionError {
+                    kind: ConversionErrorKind::UnsupportedType {
+                        signature: signature.to_string(),
+                    },
+                    span: expr.span.clone(),
+                }
+            })?;
+            to_structure(&types, fields, &expr.span).map(Value::Structure)
+        }
+        (b'h', _) => Err(ConversionError {
+            kind: ConversionErrorKind::UnsupportedType {
+                signature: signature.to_string(),
+            },
+            span: expr.span.clone(),
+        }),
+        _ => Err(mismatch()),
+    }
+}
+
+fn to_dict(signature: &str, entries: &[(Expr, Expr)]) -> Result<Value<'static>, ConversionError> {
+    // the signature has been split before, so it's a valid `a{kv}`
+    let key_signature = &signature[2..3];
+    let value_signature = &signature[3..signature.len() - 1];
+    let mut dict = Dict::new(
+        Signature::from_string_unchecked(key_signature.to_string()),
+        Signature::from_string_unchecked(value_signature.to_string()),
+    );
+
+    for (key, value) in entries {
+        dict.append(
+            to_value(key_signature, key)?,
+            to_value(value_signature, value)?,
+        )
+        .map_err(|_| ConversionError {
+            kind: ConversionErrorKind::TypeMismatch {
+                expected: signature.to_string(),
+            },
+            span: key.span.clone(),
+        })?;
+    }
+
+    Ok(Value::Dict(dict))
+}
+
+/// Converts an integer literal, distinguishing negative numbers for unsigned types from
+/// numbers that are out of range otherwise.
+fn to_integer<T: TryFrom<i128>>(
+    signature: &str,
+    number: &str,
+    expr: &Expr,
+) -> Result<T, ConversionError> {
+    let error = |kind| ConversionError {
+        kind,
+        span: expr.span.clone(),
+    };
+    let out_of_range = |negative| {
+        error(if negative && "yqut".contains(signature) {
+            ConversionErrorKind::NegativeUnsigned {
+                expected: signature.to_string(),
+            }
+        } else {
+            ConversionErrorKind::OutOfRange {
+                expected: signature.to_string(),
+            }
+        })
+    };
+    let value = parse_integer(number).map_err(|e| match e.kind() {
+        // too large even for an i128, which no D-Bus type holds
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+            out_of_range(number.starts_with('-'))
+        }
+        _ => error(match parse_float(number) {
+            Some(_) => ConversionErrorKind::TypeMismatch {
+                expected: signature.to_string(),
+            },
+            None => ConversionErrorKind::InvalidNumber,
+        }),
+    })?;
+
+    T::try_from(value).map_err(|_| out_of_range(value < 0))
+}
+
+/// Parses decimal, `0x` hexadecimal, `0o` octal and `0b` binary integer literals.
+fn parse_integer(number: &str) -> Result<i128, ParseIntError> {
+    let (negative, digits) = match number.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, number),
+    };
+
+    let value = match digits.get(..2) {
+        Some("0x" | "0X") => i128::from_str_radix(&digits[2..], 16),
+        Some("0o" | "0O") => i128::from_str_radix(&digits[2..], 8),
+        Some("0b" | "0B") => i128::from_str_radix(&digits[2..], 2),
+        _ => digits.parse(),
+    }?;
+
+    Ok(if negative { -value } else { value })
+}
+
+fn parse_float(number: &str) -> Option<f64> {
+    number
+        .parse()
+        .ok()
+        .or_else(|| parse_integer(number).ok().map(|value| value as f64))
+}
+
+/// Infers the complete type of a variant from its value: strings become `s`, booleans `b`,
+/// integers `i` (or `x` if they don't fit), other numbers `d`, and containers are typed by their elements,
+/// falling back to `v` elements if those differ.
+fn infer_signature(expr: &Expr) -> Result<String, ConversionError> {
+    match &expr.kind {
+        ExprKind::String(_) => Ok(String::from("s")),
+        ExprKind::Boolean(_) => Ok(String::from("b")),
+        ExprKind::Number(number) => match parse_integer(number).ok() {
+            Some(value) if i32::try_from(value).is_ok() => Ok(String::from("i")),
+            Some(_) => Ok(String::from("x")),
+            None => Ok(String::from("d")),
+        },
+        ExprKind::Struct(fields) if !fields.is_empty() => fields
+            .iter()
+            .map(infer_signature)
+            .collect::<Result<String, ConversionError>>()
+            .map(|signature| format!("({})", signature)),
+        ExprKind::Array(elements) => {
+            common_signature(elements.iter()).map(|signature| format!("a{}", signature))
+        }
+        ExprKind::Dict(entries) => {
+            let key_signature = common_signature(entries.iter().map(|(key, _)| key))?;
+            if key_signature == "v" || key_signature.len() > 1 {
+                return Err(ConversionError {
+                    kind: ConversionErrorKind::AmbiguousVariant,
+                    span: expr.span.clone(),
+                });
+            }
+            let value_signature = common_signature(entries.iter().map(|(_, value)| value))?;
+            Ok(format!("a{{{}{}}}", key_signature, value_signature))
+        }
+        _ => Err(ConversionError {
+            kind: ConversionErrorKind::AmbiguousVariant,
+            span: expr.span.clone(),
+        }),
+    }
+}
+
+/// The signature shared by all `exprs`, or `v` if they differ or there are none.
+fn common_signature<'a, 'e: 'a>(
+    mut exprs: impl Iterator<Item = &'a Expr<'e>>,
+) -> Result<String, ConversionError> {
+    let first = match exprs.next() {
+        Some(expr) => infer_signature(expr)?,
+        None => return Ok(String::from("v")),
+    };
+
+    for expr in exprs {
+        if infer_signature(expr)? != first {
+            return Ok(String::from("v"));
+        }
+    }
+
+    Ok(first)
+}
+
+#[cfg(test)]
+mod test {
+    use zvariant::Value;
+
+    use crate::{error::DBusConsoleError, tokenizer::Span};
+
+    use super::{parse_arguments, parse_value, split_signature};
+
+    fn error_of(signature: &str, input: &str) -> (String, usize, usize) {
+        match parse_arguments(signature, input) {
+            Err(DBusConsoleError::InvalidArguments { message, span }) => {
+                (message, span.start, span.end)
+            }
+            result => panic!("Expected invalid arguments, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_split_signature() {
+        assert_eq!(
+            split_signature("susssasa{sv}i").unwrap(),
+            vec!["s", "u", "s", "s", "s", "as", "a{sv}", "i"]
+        );
+        assert_eq!(
+            split_signature("a(ii)(s(b))").unwrap(),
+            vec!["a(ii)", "(s(b))"]
+        );
+        assert!(split_signature("a").is_err());
+        assert!(split_signature("a{(i)s}").is_err());
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        let structure =
+            parse_arguments("sia{ss}ai", "(\"str\", 1, {\"k\": \"v\"}, [1, 2])").unwrap();
+
+        assert_eq!(structure.signature(), "(sia{ss}ai)");
+        assert_eq!(structure.fields()[0], Value::from("str"));
+        assert_eq!(structure.fields()[1], Value::I32(1));
+    }
+
+    #[test]
+    fn test_parse_notify_arguments() {
+        let structure = parse_arguments(
+            "susssasa{sv}i",
+            "(\"app\", 0, \"icon\", \"summary\", \"body\", [], {\"urgency\": 1}, -1)",
+        )
+        .unwrap();
+
+        assert_eq!(structure.signature(), "(susssasa{sv}i)");
+        assert_eq!(structure.fields()[1], Value::U32(0));
+        assert_eq!(structure.fields()[7], Value::I32(-1));
+    }
+
+    #[test]
+    fn test_parse_wire_types() {
+        let structure = parse_arguments(
+            "ynqiuxtdbog(iv)",
+            "(255, -32768, 0xFFFF, 1, 0o17, -3, 4, 0.5, true, \"/org/foo\", \"a{sv}\", (1, \"v\"))",
+        )
+        .unwrap();
+
+        assert_eq!(structure.signature(), "(ynqiuxtdbog(iv))");
+        assert_eq!(structure.fields()[0], Value::U8(255));
+        assert_eq!(structure.fields()[1], Value::I16(-32768));
+        assert_eq!(structure.fields()[2], Value::U16(0xFFFF));
+        assert_eq!(structure.fields()[4], Value::U32(0o17));
+        assert_eq!(structure.fields()[7], Value::F64(0.5));
+        assert_eq!(structure.fields()[8], Value::Bool(true));
+    }
+
+    #[test]
+    fn test_parse_escaped_strings() {
+        let structure = parse_arguments("s", r#"("say \"hi\"\u{21}")"#).unwrap();
+
+        assert_eq!(structure.fields()[0], Value::from("say \"hi\"!"));
+    }
+
+    #[test]
+    fn test_parse_variants() {
+        let structure =
+            parse_arguments("vvvv", "(1.5, [1, 2], [1, \"a\"], {\"k\": [\"v\"]})").unwrap();
+        let signatures: Vec<String> = structure
+            .fields()
+            .iter()
+            .map(|field| match field {
+                Value::Value(value) => value.value_signature().to_string(),
+                value => panic!("Expected variant, got {:?}", value),
+            })
+            .collect();
+
+        assert_eq!(signatures, vec!["d", "ai", "av", "a{sas}"]);
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("u", "42").unwrap(), Value::U32(42));
+        assert_eq!(
+            parse_value("as", "[\"a\"]").unwrap().value_signature(),
+            "as"
+        );
+        assert!(parse_value("u", "(42)").is_err());
+    }
+
+    #[test]
+    fn test_parse_no_arguments() {
+        assert!(parse_arguments("", "").unwrap().fields().is_empty());
+        assert!(parse_arguments("", "()").unwrap().fields().is_empty());
+    }
+
+    #[test]
+    fn test_parse_type_mismatch() {
+        assert_eq!(
+            error_of("iu", "(1, \"str\")"),
+            (String::from("Expected a value of type \"u\""), 4, 9)
+        );
+        assert_eq!(
+            error_of("u", "(-1)"),
+            (
+                String::from("Negative number for unsigned type \"u\""),
+                1,
+                3
+            )
+        );
+        assert_eq!(
+            error_of("y", "(256)"),
+            (String::from("Number out of range for type \"y\""), 1, 4)
+        );
+        assert_eq!(
+            error_of("y", "(99999999999999999999999999999999999999999)"),
+            (String::from("Number out of range for type \"y\""), 1, 42)
+        );
+        assert_eq!(
+            error_of("t", "(-0xffffffffffffffffffffffffffffffffff)"),
+            (
+                String::from("Negative number for unsigned type \"t\""),
+                1,
+                38
+            )
+        );
+        assert_eq!(
+            error_of("i", "(1.5)"),
+            (String::from("Expected a value of type \"i\""), 1, 4)
+        );
+        assert_eq!(
+            error_of("b", "(1)"),
+            (String::from("Expected a value of type \"b\""), 1, 2)
+        );
+        assert_eq!(
+            error_of("o", "(\"no/path\")"),
+            (String::from("Invalid object path"), 1, 10)
+        );
+        assert_eq!(
+            error_of("ss", "(\"str\")"),
+            (String::from("Expected 2 values, got 1"), 0, 7)
+        );
+        assert_eq!(
+            error_of("a{sv}", "({\"k\": ()})"),
+            (String::from("Cannot infer the type of this variant"), 7, 9)
+        );
+    }
+
+    #[test]
+    fn test_parse_span_of_parser_error() {
+        let (_, start, end) = error_of("ai", "([1, 2)");
+        assert_eq!(Span { start, end }, Span { start: 6, end: 7 });
+    }
+}
@@ -0,0 +1,184 @@
+use std::{fmt::Display, os::unix::net::UnixStream};
+
+use zbus::{Connection, ConnectionBuilder};
+
+use crate::{
+    error::{DBusConsoleError, DBusConsoleResult},
+    tokenizer::Span,
+};
+
+pub const USAGE: &str =
+    "Usage: dbus-console [--session | --system | --address ADDRESS | --peer SOCKET]
+                    [--max-depth LEVELS] [--timeout MILLISECONDS]
+
+  --session          Connect to the session bus (default)
+  --system           Connect to the system bus
+  --address ADDRESS  Connect to the bus at ADDRESS, e.g. unix:path=/tmp/test-bus
+  --peer SOCKET      Connect directly to a peer listening on the unix socket SOCKET
+  --max-depth LEVELS Expand at most LEVELS levels of paths at once with * (default 32)
+  --timeout MILLISECONDS
+                     Give up on objects not introspected within MILLISECONDS (default 5000)";
+
+/// A peer ignores the destination of messages, but one is required to build a proxy.
+pub const PEER_NAME: &str = "peer.connection";
+
+/// What the console talks to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum BusAddress {
+    #[default]
+    Session,
+    System,
+    /// A bus daemon at a D-Bus address like `unix:path=/tmp/test-bus`.
+    Address(String),
+    /// A peer listening on a unix socket, without any bus daemon in between.
+    Peer(String),
+}
+
+impl BusAddress {
+    /// Reads the command line flags listed in `USAGE`, the last one wins.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut args = args.into_iter();
+        let mut address = BusAddress::default();
+
+        while let Some(arg) = args.next() {
+            address = match arg.as_str() {
+                "--session" => BusAddress::Session,
+                "--system" => BusAddress::System,
+                "--address" => BusAddress::Address(
+                    args.next()
+                        .ok_or_else(|| String::from("--address requires an ADDRESS"))?,
+                ),
+                "--peer" => BusAddress::Peer(
+                    args.next()
+                        .ok_or_else(|| String::from("--peer requires a SOCKET"))?,
+                ),
+                arg => return Err(format!("Unknown argument {}", arg)),
+            };
+        }
+
+        Ok(address)
+    }
+
+    /// Reads what's typed into the bus switcher: `session`, `system`, `peer:SOCKET` or a
+    /// D-Bus address.
+    pub fn parse(input: &str) -> DBusConsoleResult<Self> {
+        let input = input.trim();
+        match input {
+            "session" => Ok(BusAddress::Session),
+            "system" => Ok(BusAddress::System),
+            _ => match input.strip_prefix("peer:") {
+                Some(socket) if !socket.is_empty() => Ok(BusAddress::Peer(socket.to_string())),
+                // every D-Bus address starts with its transport, e.g. `unix:`
+                None if input.contains(':') => Ok(BusAddress::Address(input.to_string())),
+                _ => Err(DBusConsoleError::InvalidArguments {
+                    message: String::from(
+                        "Expected session, system, peer:SOCKET or an address like unix:path=...",
+                    ),
+                    span: Span {
+                        start: 0,
+                        end: input.len(),
+                    },
+                }),
+            },
+        }
+    }
+
+    pub async fn connect(&self) -> DBusConsoleResult<Connection> {
+        let con = match self {
+            BusAddress::Session => Connection::session().await?,
+            BusAddress::System => Connection::system().await?,
+            BusAddress::Address(address) => {
+                ConnectionBuilder::address(address.as_str())?
+                    .build()
+                    .await?
+            }
+            BusAddress::Peer(socket) => {
+                let stream = UnixStream::connect(socket)
+                    .map_err(|e| DBusConsoleError::dbus(format!("{}: {}", socket, e)))?;
+                ConnectionBuilder::unix_stream(stream).p2p().build().await?
+            }
+        };
+
+        Ok(con)
+    }
+
+    pub fn is_peer(&self) -> bool {
+        matches!(self, BusAddress::Peer(_))
+    }
+}
+
+/// Renders the address in the form `parse` accepts.
+impl Display for BusAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BusAddress::Session => write!(f, "session"),
+            BusAddress::System => write!(f, "system"),
+            BusAddress::Address(address) => write!(f, "{}", address),
+            BusAddress::Peer(socket) => write!(f, "peer:{}", socket),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{os::unix::net::UnixListener, thread};
+
+    use zbus::{blocking::ConnectionBuilder, Guid};
+
+    use super::BusAddress;
+
+    fn args(args: &[&str]) -> Result<BusAddress, String> {
+        BusAddress::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_from_args() {
+        assert_eq!(args(&[]), Ok(BusAddress::Session));
+        assert_eq!(args(&["--system"]), Ok(BusAddress::System));
+        assert_eq!(
+            args(&["--address", "unix:path=/tmp/test-bus"]),
+            Ok(BusAddress::Address(String::from("unix:path=/tmp/test-bus")))
+        );
+        assert_eq!(
+            args(&["--peer", "/tmp/peer"]),
+            Ok(BusAddress::Peer(String::from("/tmp/peer")))
+        );
+        assert!(args(&["--peer"]).is_err());
+        assert!(args(&["--bus"]).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        for address in [
+            BusAddress::Session,
+            BusAddress::System,
+            BusAddress::Address(String::from("unix:path=/tmp/test-bus")),
+            BusAddress::Peer(String::from("/tmp/peer")),
+        ] {
+            assert_eq!(BusAddress::parse(&address.to_string()), Ok(address));
+        }
+        assert!(BusAddress::parse("sessio").is_err());
+        assert!(BusAddress::parse("peer:").is_err());
+    }
+
+    #[async_std::test]
+    async fn test_connect_peer() {
+        let socket = std::env::temp_dir().join(format!("dbus-console-{}", std::process::id()));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            ConnectionBuilder::unix_stream(stream)
+                .server(&Guid::generate())
+                .p2p()
+                .build()
+                .unwrap()
+        });
+
+        let address = BusAddress::Peer(socket.to_string_lossy().to_string());
+        assert!(address.connect().await.is_ok());
+        server.join().unwrap();
+        std::fs::remove_file(&socket).unwrap();
+    }
+}
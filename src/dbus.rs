@@ -1,104 +1,837 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
 
+use futures::{stream, Stream, StreamExt};
+use serde::{de::DeserializeOwned, ser::Serialize};
 use zbus::{
-    blocking::{Connection, Proxy},
-    xml::Node,
-    Message,
+    fdo::{DBusProxy, ManagedObjects, ObjectManagerProxy},
+    xml::{self, Node},
+    Connection, Message, Proxy,
 };
+use zvariant::{DynamicType, OwnedValue, Type, Value};
 
-use crate::error::DBusConsoleResult;
+use crate::{
+    error::{DBusConsoleError, DBusConsoleResult},
+    format::format_value,
+};
 
-pub struct DBusClient {
-    con: Connection,
+const INTROSPECTABLE: &str = "org.freedesktop.DBus.Introspectable";
+const PROPERTIES: &str = "org.freedesktop.DBus.Properties";
+pub const OBJECT_MANAGER: &str = "org.freedesktop.DBus.ObjectManager";
+
+/// An object as far as it's introspected, without the details of its interfaces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectNode {
+    pub path: String,
+    pub interfaces: Vec<String>,
+    /// The full paths of the child nodes.
+    pub children: Vec<String>,
+    /// Why the object couldn't be introspected.
+    pub error: Option<DBusConsoleError>,
+}
+
+impl ObjectNode {
+    /// Whether the object implements anything but the standard interfaces, otherwise it's
+    /// only there to hold its children.
+    pub fn has_interfaces(&self) -> bool {
+        self.interfaces
+            .iter()
+            .any(|interface| !interface.starts_with("org.freedesktop.DBus."))
+    }
+
+    /// Whether the object tells about all objects below it with `GetManagedObjects`.
+    pub fn is_object_manager(&self) -> bool {
+        self.error.is_none() && self.interfaces.iter().any(|name| name == OBJECT_MANAGER)
+    }
 }
 
-impl Default for DBusClient {
+/// Who owns a name, as far as the bus daemon and `/proc` tell.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NameDetails {
+    /// The unique name of the owning connection.
+    pub owner: String,
+    /// The connections waiting to own the name once `owner` releases it, in order.
+    pub queued: Vec<String>,
+    pub pid: Option<u32>,
+    pub uid: Option<u32>,
+    /// The credentials not covered by `pid` and `uid`, e.g. the security label.
+    pub credentials: Vec<(String, String)>,
+    pub exe: Option<String>,
+    /// The arguments separated by spaces.
+    pub cmdline: Option<String>,
+}
+
+/// A name taken, released or passed on, the owners are unique names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameOwnerChange {
+    pub name: String,
+    pub old_owner: Option<String>,
+    pub new_owner: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemberKind {
+    Interface,
+    Method,
+    Signal,
+    Property,
+}
+
+/// Where an interface or one of its members was found by `DBusClient::index_members`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedMember {
+    pub service: String,
+    pub path: String,
+    pub interface: String,
+    pub kind: MemberKind,
+    /// The name of the member, for interfaces the name of the interface.
+    pub name: String,
+}
+
+/// Reported by an object manager for an object below it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectChange {
+    InterfacesAdded {
+        path: String,
+        interfaces: Vec<String>,
+    },
+    InterfacesRemoved {
+        path: String,
+        interfaces: Vec<String>,
+    },
+}
+
+/// Limits how far and how long the discovery of paths goes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Discovery {
+    /// Levels introspected at most below the path discovery starts at.
+    pub max_depth: usize,
+    /// How long a single introspection may take.
+    pub timeout: Duration,
+    /// How many introspections run at the same time.
+    pub concurrency: usize,
+}
+
+impl Default for Discovery {
     fn default() -> Self {
         Self {
-            con: Connection::session().unwrap(),
+            max_depth: 32,
+            timeout: Duration::from_secs(5),
+            concurrency: 16,
+        }
+    }
+}
+
+impl Discovery {
+    /// Takes `--max-depth LEVELS` and `--timeout MILLISECONDS` out of `args`, the others are
+    /// returned.
+    pub fn from_args(args: Vec<String>) -> Result<(Self, Vec<String>), String> {
+        let mut discovery = Discovery::default();
+        let mut rest = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--max-depth" => discovery.max_depth = number_arg(&arg, args.next())?,
+                "--timeout" => {
+                    discovery.timeout = Duration::from_millis(number_arg(&arg, args.next())? as u64)
+                }
+                _ => rest.push(arg),
+            }
+        }
+
+        Ok((discovery, rest))
+    }
+}
+
+fn number_arg(flag: &str, value: Option<String>) -> Result<usize, String> {
+    value
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| format!("{} requires a number", flag))
+}
+
+/// Deprecated members are still listed, but marked.
+pub const DEPRECATED: &str = "org.freedesktop.DBus.Deprecated";
+/// The method never replies, its callers shouldn't wait for one.
+pub const NO_REPLY: &str = "org.freedesktop.DBus.Method.NoReply";
+
+/// Everything the introspection data tells about an interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interface {
+    pub name: String,
+    pub methods: Vec<Method>,
+    pub signals: Vec<SignalMember>,
+    /// Without values, see `DBusClient::get_properties` for those.
+    pub properties: Vec<Property>,
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Method {
+    pub interface: String,
+    pub name: String,
+    pub in_signature: String,
+    pub out_signature: String,
+    pub in_args: Vec<Arg>,
+    pub out_args: Vec<Arg>,
+    pub annotations: Vec<Annotation>,
+}
+
+/// A signal as declared by an interface, see `signals::Signal` for received ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalMember {
+    pub interface: String,
+    pub name: String,
+    pub args: Vec<Arg>,
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub interface: String,
+    pub name: String,
+    pub signature: String,
+    /// One of `read`, `write` or `readwrite`.
+    pub access: String,
+    /// The formatted current value, `None` if it couldn't be read.
+    pub value: Option<String>,
+    /// Why `GetAll` failed for the interface, shared by all its properties.
+    pub error: Option<DBusConsoleError>,
+    pub annotations: Vec<Annotation>,
+}
+
+impl Property {
+    pub fn is_writable(&self) -> bool {
+        self.access.contains("write")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arg {
+    /// Arguments may be left unnamed.
+    pub name: Option<String>,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub name: String,
+    pub value: String,
+}
+
+/// Whether `annotations` contain `name` set to `true`, as e.g. `DEPRECATED` is used.
+pub fn is_annotated(annotations: &[Annotation], name: &str) -> bool {
+    annotations
+        .iter()
+        .any(|annotation| annotation.name == name && annotation.value == "true")
+}
+
+#[derive(Debug, Clone)]
+pub struct DBusClient {
+    con: Connection,
+    /// Shared by all clones of the client.
+    cache: Arc<Mutex<IntrospectionCache>>,
+}
+
+/// What `introspect` returned, kept until the owner of the name changes.
+#[derive(Debug, Default)]
+struct IntrospectionCache {
+    /// By the unique name of the owner and the path.
+    nodes: HashMap<(String, String), Arc<Node>>,
+    /// The unique name owning a well-known name.
+    owners: HashMap<String, String>,
+}
+
+impl IntrospectionCache {
+    fn owner_changed(&mut self, name: &str, old_owner: Option<&str>) {
+        self.owners.remove(name);
+        if let Some(old_owner) = old_owner {
+            self.nodes.retain(|(owner, _), _| owner != old_owner);
         }
     }
 }
 
 impl DBusClient {
     pub fn new(con: Connection) -> Self {
-        Self { con }
+        Self {
+            con,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Drops cached introspection data whenever the owner of a name changes, until the client
+    /// and all its clones are gone.
+    pub async fn watch_name_owners(&self) -> DBusConsoleResult<()> {
+        let mut changes = self.receive_name_owner_changes().await?.boxed();
+        let cache = Arc::downgrade(&self.cache);
+
+        async_std::task::spawn(async move {
+            while let Some(change) = changes.next().await {
+                let cache = match cache.upgrade() {
+                    Some(cache) => cache,
+                    None => break,
+                };
+                lock(&cache).owner_changed(&change.name, change.old_owner.as_deref());
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Receives every name appearing on the bus, vanishing or changing its owner.
+    pub async fn receive_name_owner_changes(
+        &self,
+    ) -> DBusConsoleResult<impl Stream<Item = NameOwnerChange>> {
+        let changes = DBusProxy::new(&self.con)
+            .await?
+            .receive_name_owner_changed()
+            .await?;
+
+        Ok(changes.filter_map(|change| async move {
+            let args = change.args().ok()?;
+            Some(NameOwnerChange {
+                name: args.name().to_string(),
+                old_owner: args.old_owner().as_ref().map(|owner| owner.to_string()),
+                new_owner: args.new_owner().as_ref().map(|owner| owner.to_string()),
+            })
+        }))
+    }
+
+    /// Makes the next introspection of anything ask the services again.
+    pub fn clear_cache(&self) {
+        let mut cache = lock(&self.cache);
+        cache.nodes.clear();
+        cache.owners.clear();
+    }
+
+    pub async fn list_names(&self) -> DBusConsoleResult<Vec<String>> {
+        self.call_bus("ListNames", &()).await
+    }
+
+    /// Looks up the owner of `name` and its process, only the owner is required.
+    pub async fn get_name_details(&self, name: &str) -> DBusConsoleResult<NameDetails> {
+        let owner = self.get_name_owner(name).await?;
+        let queued: Vec<String> = match name.starts_with(':') {
+            true => Vec::new(),
+            false => self
+                .call_bus("ListQueuedOwners", &(name,))
+                .await
+                .unwrap_or_default(),
+        };
+        let pid: Option<u32> = self
+            .call_bus("GetConnectionUnixProcessID", &(&owner,))
+            .await
+            .ok();
+        let uid = self
+            .call_bus("GetConnectionUnixUser", &(&owner,))
+            .await
+            .ok();
+        let credentials: HashMap<String, OwnedValue> = self
+            .call_bus("GetConnectionCredentials", &(&owner,))
+            .await
+            .unwrap_or_default();
+        let mut credentials: Vec<(String, String)> = credentials
+            .into_iter()
+            .filter(|(key, _)| !["ProcessID", "UnixUserID", "ProcessFD"].contains(&key.as_str()))
+            .map(|(key, value)| (key, format_value(&value)))
+            .collect();
+        credentials.sort();
+
+        // the process might run in another pid namespace, or be gone already
+        let exe = pid.and_then(|pid| std::fs::read_link(format!("/proc/{}/exe", pid)).ok());
+        let cmdline = pid.and_then(|pid| std::fs::read(format!("/proc/{}/cmdline", pid)).ok());
+
+        Ok(NameDetails {
+            queued: queued
+                .into_iter()
+                .filter(|queued| *queued != owner)
+                .collect(),
+            owner,
+            pid,
+            uid,
+            credentials,
+            exe: exe.map(|exe| exe.to_string_lossy().to_string()),
+            cmdline: cmdline.map(|cmdline| {
+                cmdline
+                    .split(|byte| *byte == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(String::from_utf8_lossy)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }),
+        })
+    }
+
+    /// The names the bus daemon starts a service for when they're called, running or not.
+    pub async fn list_activatable_names(&self) -> DBusConsoleResult<Vec<String>> {
+        self.call_bus("ListActivatableNames", &()).await
+    }
+
+    /// Starts the service activatable as `name`, returns 1 if it was started and 2 if it was
+    /// running already.
+    pub async fn start_service_by_name(&self, name: &str) -> DBusConsoleResult<u32> {
+        self.call_bus("StartServiceByName", &(name, 0u32)).await
+    }
+
+    pub async fn get_name_owner(&self, name: &str) -> DBusConsoleResult<String> {
+        self.call_bus("GetNameOwner", &(name,)).await
     }
 
-    pub fn list_names(&self) -> Vec<String> {
-        let proxy = Proxy::new(
-            &self.con,
+    /// Calls `member` of the bus daemon itself.
+    async fn call_bus<B, R>(&self, member: &str, body: &B) -> DBusConsoleResult<R>
+    where
+        B: Serialize + DynamicType,
+        R: DeserializeOwned + Type,
+    {
+        self.call(
             "org.freedesktop.DBus",
             "/org/freedesktop/DBus",
             "org.freedesktop.DBus",
+            member,
+            body,
         )
-        .unwrap();
-
-        proxy.call_method("ListNames", &()).unwrap().body().unwrap()
+        .await
     }
 
-    pub fn introspect(&self, service: &str, path: &str) -> DBusConsoleResult<Node> {
-        let proxy = Proxy::new(
-            &self.con,
-            service,
-            path,
-            "org.freedesktop.DBus.Introspectable",
-        )
-        .unwrap();
+    /// Introspects `path`, or takes what the current owner of `service` returned before.
+    pub async fn introspect(&self, service: &str, path: &str) -> DBusConsoleResult<Arc<Node>> {
+        let key = (self.owner_of(service).await, path.to_string());
+        if let Some(node) = lock(&self.cache).nodes.get(&key) {
+            return Ok(node.clone());
+        }
 
-        Ok(Node::from_str(&proxy.introspect()?)?)
+        let xml: String = self
+            .call(service, path, INTROSPECTABLE, "Introspect", &())
+            .await?;
+        let node = Node::from_str(&xml).map_err(|e| {
+            DBusConsoleError::from(e).during(service, path, INTROSPECTABLE, "Introspect")
+        })?;
+
+        let node = Arc::new(node);
+        lock(&self.cache).nodes.insert(key, node.clone());
+        Ok(node)
     }
 
-    pub fn get_paths(&self, service: &str) -> DBusConsoleResult<Vec<String>> {
-        self.do_get_paths(service, "/")
+    /// The unique name owning `service`, or `service` itself if it's unique already or there's
+    /// no bus daemon to ask.
+    async fn owner_of(&self, service: &str) -> String {
+        if service.starts_with(':') {
+            return service.to_string();
+        }
+        if let Some(owner) = lock(&self.cache).owners.get(service) {
+            return owner.to_owned();
+        }
+
+        // asked again next time, the name may not be owned yet
+        let owner = match self.get_name_owner(service).await {
+            Ok(owner) => owner,
+            Err(_) => return service.to_string(),
+        };
+        lock(&self.cache)
+            .owners
+            .insert(service.to_string(), owner.to_owned());
+        owner
     }
 
-    fn do_get_paths(&self, service: &str, path: &str) -> DBusConsoleResult<Vec<String>> {
-        self.introspect(service, path)?.nodes().iter_mut().fold(
-            Ok(Vec::new()),
-            |mut paths, node| {
-                let mut full_name = String::from(path);
+    /// Introspects `path` and the objects up to `depth` levels below it, level by level with
+    /// the nodes of a level introspected concurrently. `progress` gets each level as soon as
+    /// it's done. Objects failing to introspect keep their error and have no children, they
+    /// don't stop the discovery of the others. Below an object manager all objects are taken
+    /// from `GetManagedObjects` instead, however deep they are.
+    pub async fn discover(
+        &self,
+        service: &str,
+        path: &str,
+        depth: usize,
+        discovery: &Discovery,
+        progress: impl Fn(&[ObjectNode]),
+    ) -> Vec<ObjectNode> {
+        // a misbehaving service might list a node below several parents
+        let mut visited = HashSet::from([path.to_string()]);
+        let mut level = vec![path.to_string()];
+        let mut nodes = Vec::new();
+
+        for level_depth in 0..=depth {
+            if level.is_empty() {
+                break;
+            }
 
-                if !full_name.ends_with("/") {
-                    full_name.push('/')
-                }
+            let mut found: Vec<ObjectNode> = stream::iter(level)
+                .map(|path| self.try_get_object(service, path, discovery.timeout))
+                .buffer_unordered(discovery.concurrency.max(1))
+                .collect()
+                .await;
 
-                full_name.push_str(node.name().unwrap());
-
-                match paths {
-                    Ok(mut vec) => {
-                        vec.push(full_name.clone());
-                        match self.do_get_paths(service, &full_name) {
-                            Ok(mut rec_paths) => {
-                                vec.append(&mut rec_paths);
-                                Ok(vec)
-                            }
-                            Err(e) => Err(e),
-                        }
-                    }
-                    Err(e) => Err(e),
+            let mut managed = Vec::new();
+            for node in found.iter_mut().filter(|node| node.is_object_manager()) {
+                // an object manager failing to answer is still introspected the usual way
+                let objects = async_std::future::timeout(
+                    discovery.timeout,
+                    self.get_managed_objects(service, node),
+                );
+                if let Ok(Ok(objects)) = objects.await {
+                    *node = objects[0].clone();
+                    managed.extend(
+                        objects
+                            .into_iter()
+                            .skip(1)
+                            .filter(|object| visited.insert(object.path.to_owned())),
+                    );
                 }
-            },
-        )
+            }
+            progress(&found);
+            progress(&managed);
+
+            level = if level_depth < depth {
+                found
+                    .iter()
+                    .flat_map(|node| node.children.iter())
+                    .filter(|child| visited.insert(child.to_string()))
+                    .cloned()
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            nodes.extend(found);
+            nodes.extend(managed);
+        }
+
+        nodes
     }
 
-    pub fn get_methods(&self, service: &str, path: &str) -> DBusConsoleResult<Vec<String>> {
+    /// The object manager `manager` followed by all objects below it, including the ones only
+    /// there to hold others.
+    pub async fn get_managed_objects(
+        &self,
+        service: &str,
+        manager: &ObjectNode,
+    ) -> DBusConsoleResult<Vec<ObjectNode>> {
+        let managed: ManagedObjects = self
+            .call(
+                service,
+                &manager.path,
+                OBJECT_MANAGER,
+                "GetManagedObjects",
+                &(),
+            )
+            .await?;
+
+        let mut objects = HashMap::from([(manager.path.to_owned(), manager.clone())]);
+        for (path, interfaces) in managed {
+            let interfaces = interfaces
+                .into_keys()
+                .map(|name| name.to_string())
+                .collect();
+            add_interfaces(&mut objects, path.as_str(), interfaces);
+        }
+
+        let mut nodes = vec![objects
+            .remove(&manager.path)
+            .unwrap_or_else(|| manager.clone())];
+        nodes.extend(objects.into_values());
+        Ok(nodes)
+    }
+
+    /// Receives the objects added below the object manager at `path` and the ones removed.
+    pub async fn receive_object_changes(
+        &self,
+        service: &str,
+        path: &str,
+    ) -> DBusConsoleResult<impl Stream<Item = ObjectChange>> {
+        let proxy = async {
+            ObjectManagerProxy::builder(&self.con)
+                .destination(service.to_string())?
+                .path(path.to_string())?
+                .build()
+                .await
+        };
+        let proxy = proxy.await.map_err(|e| {
+            DBusConsoleError::from(e).during(service, path, OBJECT_MANAGER, "InterfacesAdded")
+        })?;
+        let added = proxy.receive_interfaces_added().await.map_err(|e| {
+            DBusConsoleError::from(e).during(service, path, OBJECT_MANAGER, "InterfacesAdded")
+        })?;
+        let removed = proxy.receive_interfaces_removed().await.map_err(|e| {
+            DBusConsoleError::from(e).during(service, path, OBJECT_MANAGER, "InterfacesRemoved")
+        })?;
+
+        let added = added.filter_map(|signal| async move {
+            let args = signal.args().ok()?;
+            Some(ObjectChange::InterfacesAdded {
+                path: args.object_path().to_string(),
+                interfaces: args
+                    .interfaces_and_properties()
+                    .keys()
+                    .map(|name| name.to_string())
+                    .collect(),
+            })
+        });
+        let removed = removed.filter_map(|signal| async move {
+            let args = signal.args().ok()?;
+            Some(ObjectChange::InterfacesRemoved {
+                path: args.object_path().to_string(),
+                interfaces: args
+                    .interfaces()
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            })
+        });
+
+        Ok(stream::select(added, removed))
+    }
+
+    /// Like `get_object`, but failures and calls taking longer than `timeout` end up in the
+    /// node's `error`.
+    async fn try_get_object(&self, service: &str, path: String, timeout: Duration) -> ObjectNode {
+        let result = async_std::future::timeout(timeout, self.get_object(service, &path))
+            .await
+            .unwrap_or_else(|_| {
+                Err(
+                    DBusConsoleError::dbus(format!("No reply within {} ms", timeout.as_millis()))
+                        .during(service, &path, INTROSPECTABLE, "Introspect"),
+                )
+            });
+
+        result.unwrap_or_else(|error| ObjectNode {
+            path,
+            interfaces: Vec::new(),
+            children: Vec::new(),
+            error: Some(error),
+        })
+    }
+
+    pub async fn get_object(&self, service: &str, path: &str) -> DBusConsoleResult<ObjectNode> {
+        let node = self.introspect(service, path).await?;
+
+        Ok(ObjectNode {
+            path: path.to_string(),
+            interfaces: node
+                .interfaces()
+                .iter()
+                .map(|interface| interface.name().to_string())
+                .collect(),
+            // only the root node may leave out its name, a child without one can't be addressed,
+            // and one containing slashes would point somewhere else
+            children: node
+                .nodes()
+                .iter()
+                .filter_map(|child| child.name())
+                .filter(|name| !name.is_empty() && !name.contains('/'))
+                .map(|name| child_path(path, name))
+                .collect(),
+            error: None,
+        })
+    }
+
+    pub async fn get_interfaces(
+        &self,
+        service: &str,
+        path: &str,
+    ) -> DBusConsoleResult<Vec<Interface>> {
         Ok(self
-            .introspect(service, path)?
+            .introspect(service, path)
+            .await?
             .interfaces()
-            .iter()
-            .map(|interface| interface.name().to_string())
+            .into_iter()
+            .map(to_interface)
             .collect())
     }
 
-    pub fn get_signature(
+    /// Discovers all objects of `service` and lists the interfaces they implement with their
+    /// members. The standard interfaces every object has are left out, objects failing to
+    /// introspect are skipped.
+    pub async fn index_members(&self, service: &str, discovery: &Discovery) -> Vec<IndexedMember> {
+        let nodes = self
+            .discover(service, "/", discovery.max_depth, discovery, |_| {})
+            .await;
+        let mut members = Vec::new();
+
+        for node in nodes.iter().filter(|node| node.has_interfaces()) {
+            // objects below an object manager weren't introspected during the discovery
+            let interfaces = async_std::future::timeout(
+                discovery.timeout,
+                self.get_interfaces(service, &node.path),
+            );
+            let interfaces = match interfaces.await {
+                Ok(Ok(interfaces)) => interfaces,
+                _ => continue,
+            };
+
+            for interface in interfaces {
+                if interface.name.starts_with("org.freedesktop.DBus.") {
+                    continue;
+                }
+                let member = |kind, name: &str| IndexedMember {
+                    service: service.to_string(),
+                    path: node.path.to_owned(),
+                    interface: interface.name.to_owned(),
+                    kind,
+                    name: name.to_string(),
+                };
+                members.push(member(MemberKind::Interface, &interface.name));
+                members.extend(
+                    (interface.methods.iter())
+                        .map(|method| member(MemberKind::Method, &method.name)),
+                );
+                members.extend(
+                    (interface.signals.iter())
+                        .map(|signal| member(MemberKind::Signal, &signal.name)),
+                );
+                members.extend(
+                    (interface.properties.iter())
+                        .map(|property| member(MemberKind::Property, &property.name)),
+                );
+            }
+        }
+
+        members
+    }
+
+    /// The paths of the objects of `service` implementing all of `interfaces`, in order.
+    pub async fn find_objects(
+        &self,
+        service: &str,
+        interfaces: &[String],
+        discovery: &Discovery,
+    ) -> Vec<String> {
+        let nodes = self
+            .discover(service, "/", discovery.max_depth, discovery, |_| {})
+            .await;
+        let mut paths = Vec::new();
+
+        for node in nodes {
+            let missing: Vec<&String> = interfaces
+                .iter()
+                .filter(|interface| !node.interfaces.contains(interface))
+                .collect();
+            if missing.is_empty() {
+                paths.push(node.path);
+                continue;
+            }
+            // object managers may leave out the standard interfaces of the objects they list
+            if node.error.is_some()
+                || missing
+                    .iter()
+                    .any(|interface| !interface.starts_with("org.freedesktop.DBus."))
+            {
+                continue;
+            }
+            let introspected = async_std::future::timeout(
+                discovery.timeout,
+                self.get_interfaces(service, &node.path),
+            );
+            if let Ok(Ok(introspected)) = introspected.await {
+                if missing.iter().all(|interface| {
+                    introspected
+                        .iter()
+                        .any(|introspected| introspected.name == **interface)
+                }) {
+                    paths.push(node.path);
+                }
+            }
+        }
+
+        paths.sort();
+        paths
+    }
+
+    /// Lists the properties of all interfaces at `path` with their current values, an
+    /// interface failing `GetAll` leaves only its values empty and its error with them.
+    pub async fn get_properties(
+        &self,
+        service: &str,
+        path: &str,
+    ) -> DBusConsoleResult<Vec<Property>> {
+        self.get_properties_where(service, path, |_| true).await
+    }
+
+    /// Like `get_properties`, limited to the properties of `interfaces`.
+    pub async fn get_properties_of(
+        &self,
+        service: &str,
+        path: &str,
+        interfaces: &[String],
+    ) -> DBusConsoleResult<Vec<Property>> {
+        self.get_properties_where(service, path, |name| {
+            interfaces.iter().any(|interface| interface == name)
+        })
+        .await
+    }
+
+    async fn get_properties_where(
+        &self,
+        service: &str,
+        path: &str,
+        wanted: impl Fn(&str) -> bool,
+    ) -> DBusConsoleResult<Vec<Property>> {
+        let node = self.introspect(service, path).await?;
+        let mut properties = Vec::new();
+
+        for interface in node.interfaces() {
+            if interface.properties().is_empty() || !wanted(interface.name()) {
+                continue;
+            }
+
+            let (mut values, error): (HashMap<String, OwnedValue>, _) = match self
+                .call(service, path, PROPERTIES, "GetAll", &(interface.name(),))
+                .await
+            {
+                Ok(values) => (values, None),
+                Err(e) => (HashMap::new(), Some(e)),
+            };
+
+            properties.extend(interface.properties().into_iter().map(|property| {
+                Property {
+                    value: values
+                        .remove(property.name())
+                        .map(|value| format_value(&value)),
+                    error: error.to_owned(),
+                    ..to_property(interface.name(), property)
+                }
+            }));
+        }
+
+        Ok(properties)
+    }
+
+    pub async fn get_property(
+        &self,
+        service: &str,
+        path: &str,
+        interface: &str,
+        name: &str,
+    ) -> DBusConsoleResult<OwnedValue> {
+        self.call(service, path, PROPERTIES, "Get", &(interface, name))
+            .await
+    }
+
+    pub async fn set_property(
+        &self,
+        service: &str,
+        path: &str,
+        interface: &str,
+        name: &str,
+        value: Value<'_>,
+    ) -> DBusConsoleResult<()> {
+        self.call_function(service, path, PROPERTIES, "Set", &(interface, name, value))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_signature(
         &self,
         service: &str,
         path: &str,
         interface: &str,
         method: &str,
     ) -> DBusConsoleResult<Option<String>> {
-        let node = self.introspect(service, path)?;
+        let node = self.introspect(service, path).await?;
 
         Ok(node
             .interfaces()
@@ -110,64 +843,584 @@ impl DBusClient {
                     .find(|mth| mth.name() == method)
                     .cloned()
             })
-            .map(|mth| {
-                mth.args()
-                    .iter()
-                    .filter(|arg| arg.direction() == Some("in"))
-                    .map(|arg| arg.ty())
-                    .fold(String::new(), |a, b| a + b)
-            }))
+            .map(|mth| signature_of(mth, "in")))
     }
 
-    pub fn call_function<T>(
+    pub async fn call_function<T>(
         &self,
         service: &str,
         path: &str,
         interface: &str,
         method: &str,
         args: &T,
-    ) -> Arc<Message>
+    ) -> DBusConsoleResult<Arc<Message>>
     where
-        T: serde::ser::Serialize + zvariant::DynamicType,
+        T: Serialize + DynamicType,
     {
-        let proxy = Proxy::new(&self.con, service, path, interface).unwrap();
+        let reply = async {
+            Proxy::new(&self.con, service, path, interface)
+                .await?
+                .call_method(method, args)
+                .await
+        }
+        .await;
 
-        proxy.call_method(method, args).unwrap()
+        reply.map_err(|e| DBusConsoleError::from(e).during(service, path, interface, method))
+    }
+
+    /// Calls `member` and reads the reply's body, errors tell which call failed.
+    async fn call<B, R>(
+        &self,
+        service: &str,
+        path: &str,
+        interface: &str,
+        member: &str,
+        body: &B,
+    ) -> DBusConsoleResult<R>
+    where
+        B: Serialize + DynamicType,
+        R: DeserializeOwned + Type,
+    {
+        self.call_function(service, path, interface, member, body)
+            .await?
+            .body()
+            .map_err(|e| DBusConsoleError::from(e).during(service, path, interface, member))
     }
 }
 
+fn to_interface(interface: &xml::Interface) -> Interface {
+    let name = interface.name();
+
+    Interface {
+        name: name.to_string(),
+        methods: interface
+            .methods()
+            .into_iter()
+            .map(|method| Method {
+                interface: name.to_string(),
+                name: method.name().to_string(),
+                in_signature: signature_of(method, "in"),
+                out_signature: signature_of(method, "out"),
+                in_args: args_of(method.args(), "in"),
+                out_args: args_of(method.args(), "out"),
+                annotations: to_annotations(method.annotations()),
+            })
+            .collect(),
+        signals: interface
+            .signals()
+            .into_iter()
+            .map(|signal| SignalMember {
+                interface: name.to_string(),
+                name: signal.name().to_string(),
+                args: signal.args().into_iter().map(to_arg).collect(),
+                annotations: to_annotations(signal.annotations()),
+            })
+            .collect(),
+        properties: interface
+            .properties()
+            .into_iter()
+            .map(|property| to_property(name, property))
+            .collect(),
+        annotations: to_annotations(interface.annotations()),
+    }
+}
+
+fn to_property(interface: &str, property: &xml::Property) -> Property {
+    Property {
+        interface: interface.to_string(),
+        name: property.name().to_string(),
+        signature: property.ty().to_string(),
+        access: property.access().to_string(),
+        value: None,
+        error: None,
+        annotations: to_annotations(property.annotations()),
+    }
+}
+
+/// The arguments of a method going into `direction` ("in" or "out").
+fn args_of(args: Vec<&xml::Arg>, direction: &str) -> Vec<Arg> {
+    args.into_iter()
+        .filter(|arg| arg.direction().unwrap_or("in") == direction)
+        .map(to_arg)
+        .collect()
+}
+
+fn to_arg(arg: &xml::Arg) -> Arg {
+    Arg {
+        name: arg.name().map(str::to_string),
+        signature: arg.ty().to_string(),
+    }
+}
+
+fn to_annotations(annotations: Vec<&xml::Annotation>) -> Vec<Annotation> {
+    annotations
+        .into_iter()
+        .map(|annotation| Annotation {
+            name: annotation.name().to_string(),
+            value: annotation.value().to_string(),
+        })
+        .collect()
+}
+
+/// Adds `interfaces` to the object at `path`, which is created along with the missing objects
+/// above it if it's not known yet.
+pub fn add_interfaces(
+    objects: &mut HashMap<String, ObjectNode>,
+    path: &str,
+    interfaces: Vec<String>,
+) {
+    let node = objects
+        .entry(path.to_string())
+        .or_insert_with(|| ObjectNode {
+            path: path.to_string(),
+            interfaces: Vec::new(),
+            children: Vec::new(),
+            error: None,
+        });
+    for interface in interfaces {
+        if !node.interfaces.contains(&interface) {
+            node.interfaces.push(interface);
+        }
+    }
+
+    let mut path = path.to_string();
+    while let Some(parent) = parent_path(&path) {
+        let known = objects.contains_key(parent);
+        let parent = objects
+            .entry(parent.to_string())
+            .or_insert_with(|| ObjectNode {
+                path: parent.to_string(),
+                interfaces: Vec::new(),
+                children: Vec::new(),
+                error: None,
+            });
+        if !parent.children.contains(&path) {
+            parent.children.push(path);
+        }
+        if known {
+            break;
+        }
+        path = parent.path.to_owned();
+    }
+}
+
+/// Removes `interfaces` from the object at `path`, the object itself goes once it has neither
+/// interfaces nor children left.
+pub fn remove_interfaces(
+    objects: &mut HashMap<String, ObjectNode>,
+    path: &str,
+    interfaces: &[String],
+) {
+    let node = match objects.get_mut(path) {
+        Some(node) => node,
+        None => return,
+    };
+    node.interfaces
+        .retain(|interface| !interfaces.contains(interface));
+    if !node.interfaces.is_empty() || !node.children.is_empty() {
+        return;
+    }
+
+    objects.remove(path);
+    if let Some(parent) = parent_path(path).and_then(|parent| objects.get_mut(parent)) {
+        parent.children.retain(|child| child != path);
+    }
+}
+
+pub fn parent_path(path: &str) -> Option<&str> {
+    match path.rsplit_once('/')? {
+        ("", "") => None,
+        ("", _) => Some("/"),
+        (parent, _) => Some(parent),
+    }
+}
+
+/// The cache stays usable even if a thread panicked while holding it.
+fn lock(cache: &Mutex<IntrospectionCache>) -> MutexGuard<'_, IntrospectionCache> {
+    cache.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn child_path(parent: &str, name: &str) -> String {
+    let mut path = String::from(parent);
+    if !path.ends_with('/') {
+        path.push('/')
+    }
+    path.push_str(name);
+
+    path
+}
+
+/// Concatenates the types of all arguments of `method` going into `direction` ("in" or "out").
+fn signature_of(method: &xml::Method, direction: &str) -> String {
+    method
+        .args()
+        .iter()
+        .filter(|arg| arg.direction().unwrap_or("in") == direction)
+        .map(|arg| arg.ty())
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
-    use crate::dbus::DBusClient;
+    use zbus::{dbus_interface, fdo::ObjectManager, Connection, MessageStream};
 
-    #[test]
-    fn test_list_names() {
-        let dbus_client = DBusClient::default();
-        assert!(dbus_client.list_names().len() > 1);
+    use std::{collections::HashMap, sync::Arc, time::Duration};
+
+    use futures::StreamExt;
+
+    use crate::{
+        arguments::parse_arguments,
+        dbus::{
+            add_interfaces, remove_interfaces, DBusClient, Discovery, MemberKind, ObjectChange,
+            OBJECT_MANAGER, PROPERTIES,
+        },
+        error::DBusConsoleError,
+        format::format_body,
+    };
+
+    async fn session_client() -> DBusClient {
+        DBusClient::new(Connection::session().await.unwrap())
     }
 
-    #[test]
-    fn test_instrospect() {
-        let dbus_client = DBusClient::default();
+    #[async_std::test]
+    async fn test_list_names() {
+        let dbus_client = session_client().await;
+        assert!(dbus_client.list_names().await.unwrap().len() > 1);
+    }
+
+    #[async_std::test]
+    async fn test_activatable_names() {
+        let dbus_client = session_client().await;
+        let names = dbus_client.list_activatable_names().await.unwrap();
+        assert!(names.iter().any(|name| name == "org.freedesktop.DBus"));
+
+        // the test bus has no service files
+        let error = dbus_client
+            .start_service_by_name("org.example.NotInstalled")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.name(),
+            Some("org.freedesktop.DBus.Error.ServiceUnknown")
+        );
+    }
+
+    #[async_std::test]
+    async fn test_name_details() {
+        let service = Connection::session().await.unwrap();
+        service.request_name("org.example.Details").await.unwrap();
+
+        let dbus_client = session_client().await;
+        let details = dbus_client
+            .get_name_details("org.example.Details")
+            .await
+            .unwrap();
+
+        assert_eq!(details.owner, service.unique_name().unwrap().as_str());
+        assert!(details.queued.is_empty());
+        assert_eq!(details.pid, Some(std::process::id()));
+        let exe = std::env::current_exe().unwrap();
+        assert_eq!(details.exe.as_deref(), exe.to_str());
+        assert!(details.cmdline.is_some());
+        assert_eq!(details.uid, Some(current_uid()));
+    }
+
+    /// The uid of this process, as owner of /proc/self.
+    fn current_uid() -> u32 {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata("/proc/self").unwrap().uid()
+    }
+
+    #[async_std::test]
+    async fn test_instrospect() {
+        let dbus_client = session_client().await;
         let result = dbus_client
             .introspect(
                 "org.freedesktop.Notifications",
                 "/org/freedesktop/Notifications",
             )
+            .await
+            .unwrap();
+
+        assert!(!result.interfaces().is_empty());
+    }
+
+    #[async_std::test]
+    async fn test_owner_not_cached_until_owned() {
+        let dbus_client = session_client().await;
+        assert_eq!(
+            dbus_client.owner_of("org.example.Later").await,
+            "org.example.Later"
+        );
+
+        let service = Connection::session().await.unwrap();
+        service.request_name("org.example.Later").await.unwrap();
+        assert_eq!(
+            dbus_client.owner_of("org.example.Later").await,
+            service.unique_name().unwrap().as_str()
+        );
+    }
+
+    #[async_std::test]
+    async fn test_introspection_cache() {
+        let service = Connection::session().await.unwrap();
+        service.object_server().at("/cached", Device).await.unwrap();
+        service.request_name("org.example.Cached").await.unwrap();
+
+        let dbus_client = session_client().await;
+        dbus_client.watch_name_owners().await.unwrap();
+        let node = dbus_client
+            .introspect("org.example.Cached", "/cached")
+            .await
+            .unwrap();
+        let cached = dbus_client
+            .introspect("org.example.Cached", "/cached")
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&node, &cached));
+
+        dbus_client.clear_cache();
+        let refreshed = dbus_client
+            .introspect("org.example.Cached", "/cached")
+            .await
+            .unwrap();
+        assert!(!Arc::ptr_eq(&node, &refreshed));
+
+        service.release_name("org.example.Cached").await.unwrap();
+        let mut result = Ok(refreshed);
+        for _ in 0..50 {
+            result = dbus_client
+                .introspect("org.example.Cached", "/cached")
+                .await;
+            if result.is_err() {
+                break;
+            }
+            async_std::task::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(
+            result.unwrap_err().name(),
+            Some("org.freedesktop.DBus.Error.ServiceUnknown")
+        );
+    }
+
+    #[async_std::test]
+    async fn test_get_paths() {
+        let dbus_client = session_client().await;
+        let nodes = dbus_client
+            .discover(
+                "org.freedesktop.Notifications",
+                "/",
+                usize::MAX,
+                &Discovery::default(),
+                |_| {},
+            )
+            .await;
+
+        assert_eq!(nodes[0].path, "/");
+        let notifications = nodes
+            .iter()
+            .find(|node| node.path == "/org/freedesktop/Notifications")
             .unwrap();
+        assert!(notifications.has_interfaces());
+        assert!(!nodes[0].has_interfaces());
+    }
+
+    #[async_std::test]
+    async fn test_discover_partially() {
+        let dbus_client = session_client().await;
+        let discovery = Discovery::default();
+        let nodes = dbus_client
+            .discover("org.freedesktop.Notifications", "/", 1, &discovery, |_| {})
+            .await;
+        // /org is introspected, but not its children
+        assert_eq!(nodes.len(), 2);
 
-        assert!(result.interfaces().len() > 0);
+        let nodes = dbus_client
+            .discover("org.nonexistent", "/", 1, &discovery, |_| {})
+            .await;
+        assert_eq!(nodes.len(), 1);
+        assert!(nodes[0].error.is_some());
+    }
+
+    struct Device;
+
+    #[dbus_interface(name = "org.example.Device")]
+    impl Device {
+        fn eject(&self) {}
+
+        #[dbus_interface(property)]
+        fn label(&self) -> String {
+            String::from("disk")
+        }
+    }
+
+    #[async_std::test]
+    async fn test_object_manager() {
+        let service = Connection::session().await.unwrap();
+        let server = service.object_server();
+        server.at("/om", ObjectManager).await.unwrap();
+        server.at("/om/disks/a", Device).await.unwrap();
+        let service_name = service.unique_name().unwrap().to_string();
+
+        let dbus_client = session_client().await;
+        let nodes = dbus_client
+            .discover(&service_name, "/om", 0, &Discovery::default(), |_| {})
+            .await;
+        let paths: Vec<&str> = nodes.iter().map(|node| node.path.as_str()).collect();
+        assert_eq!(paths[0], "/om");
+        assert!(paths.contains(&"/om/disks"));
+        let device = nodes
+            .iter()
+            .find(|node| node.path == "/om/disks/a")
+            .unwrap();
+        assert_eq!(device.interfaces, vec!["org.example.Device"]);
+
+        let mut changes = dbus_client
+            .receive_object_changes(&service_name, "/om")
+            .await
+            .unwrap()
+            .boxed();
+        server.at("/om/disks/b", Device).await.unwrap();
+        assert_eq!(
+            changes.next().await.unwrap(),
+            ObjectChange::InterfacesAdded {
+                path: String::from("/om/disks/b"),
+                interfaces: vec![String::from("org.example.Device")]
+            }
+        );
+    }
+
+    #[async_std::test]
+    async fn test_index_members() {
+        let service = Connection::session().await.unwrap();
+        let server = service.object_server();
+        server.at("/om", ObjectManager).await.unwrap();
+        server.at("/om/disks/a", Device).await.unwrap();
+        server.at("/drive", Device).await.unwrap();
+        let service_name = service.unique_name().unwrap().to_string();
+
+        let dbus_client = session_client().await;
+        let members = dbus_client
+            .index_members(&service_name, &Discovery::default())
+            .await;
+        let ejects: Vec<&str> = members
+            .iter()
+            .filter(|member| member.kind == MemberKind::Method)
+            .map(|member| member.path.as_str())
+            .collect();
+        assert_eq!(ejects.len(), 2);
+        assert!(ejects.contains(&"/drive"));
+        // found through the object manager
+        assert!(ejects.contains(&"/om/disks/a"));
+        assert!(members
+            .iter()
+            .all(|member| member.interface == "org.example.Device"));
+        assert_eq!(members[0].kind, MemberKind::Interface);
+    }
+
+    #[async_std::test]
+    async fn test_find_objects() {
+        let service = Connection::session().await.unwrap();
+        let server = service.object_server();
+        server.at("/om", ObjectManager).await.unwrap();
+        server.at("/om/disks/a", Device).await.unwrap();
+        server.at("/drive", Device).await.unwrap();
+        let service_name = service.unique_name().unwrap().to_string();
+
+        let dbus_client = session_client().await;
+        let device = String::from("org.example.Device");
+        let interfaces = [device.to_owned(), String::from(PROPERTIES)];
+        let paths = dbus_client
+            .find_objects(&service_name, &interfaces, &Discovery::default())
+            .await;
+        assert_eq!(paths, vec!["/drive", "/om/disks/a"]);
+        let paths = dbus_client
+            .find_objects(
+                &service_name,
+                &[String::from(OBJECT_MANAGER)],
+                &Discovery::default(),
+            )
+            .await;
+        assert_eq!(paths, vec!["/om"]);
+
+        let properties = dbus_client
+            .get_properties_of(&service_name, "/drive", &[device])
+            .await
+            .unwrap();
+        assert_eq!(properties.len(), 1);
+        assert_eq!(properties[0].name, "Label");
+        assert_eq!(properties[0].value.as_deref(), Some("\"disk\""));
+    }
+
+    #[async_std::test]
+    async fn test_unreadable_properties() {
+        // answers by hand, zbus' object server can't make `GetAll` fail
+        let service = Connection::session().await.unwrap();
+        let service_name = service.unique_name().unwrap().to_string();
+        let mut calls = MessageStream::from(&service);
+        async_std::task::spawn(async move {
+            let xml = r#"<node><interface name="org.example.Locked">
+                <property name="Secret" type="s" access="read"/>
+            </interface></node>"#;
+            while let Some(Ok(call)) = calls.next().await {
+                match call.member().as_ref().map(|member| member.as_str()) {
+                    Some("Introspect") => service.reply(&call, &xml).await.unwrap(),
+                    Some("GetAll") => {
+                        let name = "org.freedesktop.DBus.Error.AccessDenied";
+                        service.reply_error(&call, name, &"Locked").await.unwrap()
+                    }
+                    _ => continue,
+                };
+            }
+        });
+
+        let dbus_client = session_client().await;
+        let properties = dbus_client
+            .get_properties_of(
+                &service_name,
+                "/locked",
+                &[String::from("org.example.Locked")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(properties[0].value, None);
+        assert_eq!(
+            properties[0].error.as_ref().and_then(|e| e.name()),
+            Some("org.freedesktop.DBus.Error.AccessDenied")
+        );
     }
 
     #[test]
-    fn test_get_paths() {
-        let dbus_client = DBusClient::default();
-        let result = dbus_client.get_paths("org.freedesktop.Notifications");
+    fn test_add_and_remove_interfaces() {
+        let mut objects = HashMap::new();
+        add_interfaces(
+            &mut objects,
+            "/a/b",
+            vec![String::from("org.example.Device")],
+        );
+        assert_eq!(objects["/"].children, vec!["/a"]);
+        assert_eq!(objects["/a"].children, vec!["/a/b"]);
+
+        remove_interfaces(&mut objects, "/a/b", &[String::from("org.example.Device")]);
+        assert!(!objects.contains_key("/a/b"));
+        assert!(objects["/a"].children.is_empty());
     }
 
     #[test]
-    fn test_call() {
-        let dbus_client = DBusClient::default();
+    fn test_discovery_from_args() {
+        let args = ["--max-depth", "3", "--system", "--timeout", "100"];
+        let (discovery, rest) =
+            Discovery::from_args(args.iter().map(|arg| arg.to_string()).collect()).unwrap();
+
+        assert_eq!(discovery.max_depth, 3);
+        assert_eq!(discovery.timeout, Duration::from_millis(100));
+        assert_eq!(rest, vec!["--system"]);
+        assert!(Discovery::from_args(vec![String::from("--timeout")]).is_err());
+    }
+
+    #[async_std::test]
+    async fn test_call() {
+        let dbus_client = session_client().await;
         assert!(dbus_client
             .call_function(
                 "org.freedesktop.DBus",
@@ -176,13 +1429,70 @@ mod test {
                 "ListNames",
                 &()
             )
+            .await
+            .unwrap()
             .body::<Vec<String>>()
             .is_ok());
     }
 
-    #[test]
-    fn test_get_signature() {
-        let dbus_client = DBusClient::default();
+    #[async_std::test]
+    async fn test_call_with_arguments() {
+        let dbus_client = session_client().await;
+        let reply = dbus_client
+            .call_function(
+                "org.freedesktop.DBus",
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus",
+                "NameHasOwner",
+                &parse_arguments("s", "(\"org.freedesktop.DBus\")").unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(format_body(&reply).unwrap(), "(true)");
+    }
+
+    #[async_std::test]
+    async fn test_get_properties() {
+        let dbus_client = session_client().await;
+        let properties = dbus_client
+            .get_properties("org.freedesktop.DBus", "/org/freedesktop/DBus")
+            .await
+            .unwrap();
+
+        let features = properties
+            .iter()
+            .find(|property| property.name == "Features")
+            .unwrap();
+        assert_eq!(features.signature, "as");
+        assert!(!features.is_writable());
+        assert!(features.value.is_some());
+    }
+
+    #[async_std::test]
+    async fn test_set_read_only_property() {
+        let dbus_client = session_client().await;
+        let result = dbus_client
+            .set_property(
+                "org.freedesktop.DBus",
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus",
+                "Features",
+                zvariant::Value::from(vec!["x"]),
+            )
+            .await;
+
+        match result {
+            Err(DBusConsoleError::MethodError { name, .. }) => {
+                assert_eq!(name, "org.freedesktop.DBus.Error.PropertyReadOnly")
+            }
+            other => panic!("Expected a method error, got {:?}", other),
+        }
+    }
+
+    #[async_std::test]
+    async fn test_get_signature() {
+        let dbus_client = session_client().await;
         let result = dbus_client
             .get_signature(
                 "org.freedesktop.Notifications",
@@ -190,8 +1500,31 @@ mod test {
                 "org.freedesktop.Notifications",
                 "Notify",
             )
+            .await
             .unwrap();
 
         assert_eq!(result, Some(String::from("susssasa{sv}i")));
     }
+
+    #[async_std::test]
+    async fn test_method_error() {
+        let dbus_client = session_client().await;
+        let error = dbus_client
+            .call_function(
+                "org.freedesktop.DBus",
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus",
+                "NoSuchMethod",
+                &(),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.name(),
+            Some("org.freedesktop.DBus.Error.UnknownMethod")
+        );
+        assert_eq!(error.call().unwrap().member, "NoSuchMethod");
+        assert!(error.hint().is_some());
+    }
 }
@@ -1,21 +1,194 @@
-use zbus::{fdo::Error as ZBusFdoError, Error as ZBusError};
+use std::fmt::Display;
+
+use zbus::{fdo::Error as ZBusFdoError, DBusError, Error as ZBusError};
+
+use crate::{
+    arguments::ConversionError,
+    parser::ParserError,
+    tokenizer::{Span, TokenizerError},
+};
 
 pub type DBusConsoleResult<T> = Result<T, DBusConsoleError>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum DBusConsoleError {
-    DBusError(String),
-    FdoError(String),
+    /// An error reply, e.g. `org.freedesktop.DBus.Error.AccessDenied` with its message.
+    MethodError {
+        name: String,
+        message: String,
+        call: Option<Box<Call>>,
+    },
+    /// Anything else failing on the connection, e.g. it can't be opened or a reply is malformed.
+    DBusError {
+        message: String,
+        call: Option<Box<Call>>,
+    },
+    InvalidSignature(String),
+    /// Arguments typed in the console that can't be tokenized, parsed or converted, the span
+    /// points at the offending part of the input.
+    InvalidArguments {
+        message: String,
+        span: Span,
+    },
+}
+
+/// The method call an error belongs to, boxed in the error to keep results small.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call {
+    pub service: String,
+    pub path: String,
+    pub interface: String,
+    pub member: String,
+}
+
+impl DBusConsoleError {
+    pub fn dbus(message: impl ToString) -> Self {
+        DBusConsoleError::DBusError {
+            message: message.to_string(),
+            call: None,
+        }
+    }
+
+    /// Attaches the call that failed, errors not coming from the bus are left as they are.
+    pub fn during(self, service: &str, path: &str, interface: &str, member: &str) -> Self {
+        let call = Some(Box::new(Call {
+            service: service.to_string(),
+            path: path.to_string(),
+            interface: interface.to_string(),
+            member: member.to_string(),
+        }));
+        match self {
+            DBusConsoleError::MethodError { name, message, .. } => DBusConsoleError::MethodError {
+                name,
+                message,
+                call,
+            },
+            DBusConsoleError::DBusError { message, .. } => {
+                DBusConsoleError::DBusError { message, call }
+            }
+            e => e,
+        }
+    }
+
+    /// The D-Bus error name of an error reply.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            DBusConsoleError::MethodError { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn call(&self) -> Option<&Call> {
+        match self {
+            DBusConsoleError::MethodError { call, .. }
+            | DBusConsoleError::DBusError { call, .. } => call.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// What might help against the well-known error replies.
+    pub fn hint(&self) -> Option<&'static str> {
+        let hint = match self.name()?.strip_prefix("org.freedesktop.DBus.Error.")? {
+            "AccessDenied" | "AuthFailed" | "InteractiveAuthorizationRequired" => {
+                "The bus policy denies this, try the system bus as root"
+            }
+            "ServiceUnknown" | "NameHasNoOwner" => {
+                "The name isn't owned and can't be activated, reload the bus names"
+            }
+            "UnknownObject" => "The path doesn't exist (anymore), reload the paths",
+            "UnknownInterface" | "UnknownMethod" | "UnknownProperty" => {
+                "The object doesn't implement this, its introspection data may be stale"
+            }
+            "InvalidArgs" | "InvalidSignature" => "Check the arguments against the signature",
+            "PropertyReadOnly" => "The property can only be read",
+            "NoReply" | "Timeout" | "TimedOut" => "The service didn't answer in time",
+            "LimitsExceeded" => "The bus limits the number of connections or pending calls",
+            _ => return None,
+        };
+
+        Some(hint)
+    }
+}
+
+/// The error without its span or hint, e.g. `org.freedesktop.DBus.Error.UnknownMethod: No
+/// such method (org.foo.Bar on org.foo /)`.
+impl Display for DBusConsoleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DBusConsoleError::MethodError { name, message, .. } if message.is_empty() => {
+                write!(f, "{}", name)?
+            }
+            DBusConsoleError::MethodError { name, message, .. } => {
+                write!(f, "{}: {}", name, message)?
+            }
+            DBusConsoleError::DBusError { message, .. } => write!(f, "{}", message)?,
+            DBusConsoleError::InvalidSignature(signature) => {
+                write!(f, "Invalid signature {}", signature)?
+            }
+            DBusConsoleError::InvalidArguments { message, .. } => write!(f, "{}", message)?,
+        }
+        if let Some(call) = self.call() {
+            write!(
+                f,
+                " ({}.{} on {} {})",
+                call.interface, call.member, call.service, call.path
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 impl From<ZBusError> for DBusConsoleError {
     fn from(error: ZBusError) -> Self {
-        DBusConsoleError::DBusError(error.to_string())
+        match error {
+            ZBusError::MethodError(name, message, _) => DBusConsoleError::MethodError {
+                name: name.to_string(),
+                message: message.unwrap_or_default(),
+                call: None,
+            },
+            ZBusError::FDO(error) => DBusConsoleError::from(*error),
+            error => DBusConsoleError::dbus(error),
+        }
     }
 }
 
 impl From<ZBusFdoError> for DBusConsoleError {
     fn from(error: ZBusFdoError) -> Self {
-        DBusConsoleError::FdoError(error.to_string())
+        match error {
+            ZBusFdoError::ZBus(error) => DBusConsoleError::from(error),
+            error => DBusConsoleError::MethodError {
+                name: error.name().to_string(),
+                message: error.description().unwrap_or_default().to_string(),
+                call: None,
+            },
+        }
+    }
+}
+
+impl From<TokenizerError> for DBusConsoleError {
+    fn from(error: TokenizerError) -> Self {
+        DBusConsoleError::InvalidArguments {
+            message: error.message,
+            span: error.span,
+        }
+    }
+}
+
+impl From<ParserError> for DBusConsoleError {
+    fn from(error: ParserError) -> Self {
+        DBusConsoleError::InvalidArguments {
+            message: error.to_string(),
+            span: error.span,
+        }
+    }
+}
+
+impl From<ConversionError> for DBusConsoleError {
+    fn from(error: ConversionError) -> Self {
+        DBusConsoleError::InvalidArguments {
+            message: error.to_string(),
+            span: error.span,
+        }
     }
 }
@@ -1,12 +1,472 @@
+use std::{collections::HashMap, ops::Range};
+
 use regex::Regex;
 
-use crate::app::App;
+use crate::app::{BusContext, MemberRow, Section};
+
+/// How the query of a search is matched against the rows of a pane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchMode {
+    Substring,
+    /// The characters of the query in order, with anything in between.
+    Fuzzy,
+    Regex,
+}
+
+impl SearchMode {
+    pub fn next(self) -> Self {
+        match self {
+            SearchMode::Substring => SearchMode::Fuzzy,
+            SearchMode::Fuzzy => SearchMode::Regex,
+            SearchMode::Regex => SearchMode::Substring,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchMode::Substring => "substring",
+            SearchMode::Fuzzy => "fuzzy",
+            SearchMode::Regex => "regex",
+        }
+    }
+}
+
+/// The search of a list pane, rows not matching the query are hidden.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Search {
+    pub query: String,
+    pub mode: SearchMode,
+    /// The prompt is open and takes the keys typed.
+    pub editing: bool,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            mode: SearchMode::Substring,
+            editing: false,
+        }
+    }
+}
+
+impl Search {
+    /// `None` while the query is empty, which matches everything. Substring and fuzzy searches
+    /// ignore case unless the query contains upper case characters.
+    pub fn matcher(&self) -> Result<Option<Matcher>, regex::Error> {
+        if self.query.is_empty() {
+            return Ok(None);
+        }
+        let ignore_case = !self.query.chars().any(char::is_uppercase);
+        let query = self.query.chars().collect();
+        Ok(Some(match self.mode {
+            SearchMode::Substring => Matcher::Substring { query, ignore_case },
+            SearchMode::Fuzzy => Matcher::Fuzzy { query, ignore_case },
+            SearchMode::Regex => Matcher::Regex(Regex::new(&self.query)?),
+        }))
+    }
+}
+
+/// A search compiled for matching many rows, see `Search::matcher`.
+#[derive(Debug, Clone)]
+pub enum Matcher {
+    Substring { query: Vec<char>, ignore_case: bool },
+    Fuzzy { query: Vec<char>, ignore_case: bool },
+    Regex(Regex),
+}
+
+impl Matcher {
+    /// The byte ranges of `text` matching, `None` if it doesn't match.
+    pub fn find(&self, text: &str) -> Option<Vec<Range<usize>>> {
+        match self {
+            Matcher::Substring { query, ignore_case } => {
+                let chars: Vec<(usize, char)> = text.char_indices().collect();
+                (0..(chars.len() + 1).saturating_sub(query.len()))
+                    .find(|&start| {
+                        chars[start..start + query.len()]
+                            .iter()
+                            .zip(query)
+                            .all(|(&(_, c), &q)| same_char(c, q, *ignore_case))
+                    })
+                    .map(|start| {
+                        let end = chars
+                            .get(start + query.len())
+                            .map_or(text.len(), |&(end, _)| end);
+                        std::iter::once(chars[start].0..end).collect()
+                    })
+            }
+            Matcher::Fuzzy { query, ignore_case } => {
+                let mut ranges: Vec<Range<usize>> = Vec::new();
+                let mut query = query.iter().peekable();
+                for (index, c) in text.char_indices() {
+                    match query.peek() {
+                        Some(&&q) if same_char(c, q, *ignore_case) => {
+                            query.next();
+                            let end = index + c.len_utf8();
+                            match ranges.last_mut() {
+                                Some(last) if last.end == index => last.end = end,
+                                _ => ranges.push(index..end),
+                            }
+                        }
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+                query.peek().is_none().then_some(ranges)
+            }
+            Matcher::Regex(regex) => regex.find(text).map(|found| vec![found.range()]),
+        }
+    }
+}
+
+fn same_char(c: char, query: char, ignore_case: bool) -> bool {
+    c == query || ignore_case && c.to_lowercase().eq(query.to_lowercase())
+}
+
+/// The matcher of the search in `section`, `None` without one.
+pub fn matcher(context: &BusContext, section: &Section) -> Option<Matcher> {
+    context
+        .searches
+        .get(section)
+        .and_then(|search| search.matcher().ok().flatten())
+}
+
+/// The rows of `section` its search leaves visible, as indices into the rows of the pane. Rows
+/// of a tree stay visible while a row below them matches.
+pub fn visible_rows(context: &BusContext, section: &Section) -> Vec<usize> {
+    let rows = search_rows(context, section);
+    let matcher = match matcher(context, section) {
+        Some(matcher) => matcher,
+        None => return (0..rows.len()).collect(),
+    };
+
+    let mut visible = vec![false; rows.len()];
+    for (index, (depth, text)) in rows.iter().enumerate() {
+        if visible[index] || matcher.find(text).is_none() {
+            continue;
+        }
+        visible[index] = true;
+        let mut depth = *depth;
+        for ancestor in (0..index).rev() {
+            if depth == 0 {
+                break;
+            }
+            if rows[ancestor].0 < depth {
+                visible[ancestor] = true;
+                depth = rows[ancestor].0;
+            }
+        }
+    }
+
+    (0..rows.len()).filter(|&index| visible[index]).collect()
+}
+
+/// The depth and searched text of the rows of `section`: the name column, the full path for
+/// paths.
+fn search_rows(context: &BusContext, section: &Section) -> Vec<(usize, String)> {
+    match section {
+        Section::BusFrame => name_rows(context)
+            .into_iter()
+            .map(|row| (row.depth, row.name.to_owned()))
+            .collect(),
+        Section::BusPath => context
+            .paths
+            .entries
+            .iter()
+            .map(|path| (path_depth(path), path.to_owned()))
+            .collect(),
+        Section::Methods => context
+            .members
+            .entries
+            .iter()
+            .map(|row| {
+                let interface = &context.interfaces[row.interface()];
+                match *row {
+                    MemberRow::Interface(_) => (0, interface.name.to_owned()),
+                    MemberRow::Method(_, index) => (1, interface.methods[index].name.to_owned()),
+                    MemberRow::Signal(_, index) => (1, interface.signals[index].name.to_owned()),
+                    MemberRow::Property(_, index) => {
+                        (1, interface.properties[index].name.to_owned())
+                    }
+                }
+            })
+            .collect(),
+        Section::Properties => context
+            .properties
+            .entries
+            .iter()
+            .map(|property| (0, property.name.to_owned()))
+            .collect(),
+        Section::Signals => context
+            .signals
+            .entries
+            .iter()
+            .map(|signal| (0, signal.member_label()))
+            .collect(),
+        Section::Monitor => context
+            .monitored_messages
+            .entries
+            .iter()
+            .map(|message| (0, message.member_label()))
+            .collect(),
+        Section::MemberSearch => context
+            .member_index
+            .entries
+            .iter()
+            .map(|member| (0, member.name.to_owned()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// How deep `path` is in the object tree, the root is at depth 0.
+pub fn path_depth(path: &str) -> usize {
+    path.matches('/').count() - usize::from(path == "/")
+}
+
+/// The row after `selected` among the `visible` ones, the first without a selection.
+pub fn next_visible(selected: Option<u32>, visible: &[usize]) -> Option<usize> {
+    match selected {
+        Some(selected) => visible
+            .iter()
+            .find(|&&index| index > selected as usize)
+            .copied(),
+        None => visible.first().copied(),
+    }
+}
+
+/// The row before `selected` among the `visible` ones, the first without a selection.
+pub fn previous_visible(selected: Option<u32>, visible: &[usize]) -> Option<usize> {
+    match selected {
+        Some(selected) => visible
+            .iter()
+            .rev()
+            .find(|&&index| index < selected as usize)
+            .copied(),
+        None => visible.first().copied(),
+    }
+}
+
+/// A row of the bus name list, names owned by a connection are indented below it when grouped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameRow<'a> {
+    pub name: &'a String,
+    pub depth: usize,
+    /// Waiting in the queue of `name` for the connection above to release it.
+    pub queued: bool,
+    /// The connection the row is listed below, `None` unless grouped.
+    pub group: Option<&'a String>,
+}
+
+pub fn filter_bus_names(context: &BusContext) -> impl DoubleEndedIterator<Item = &String> {
+    name_rows(context).into_iter().map(|row| row.name)
+}
 
-pub fn filter_bus_names<'a>(app: &'a App) -> impl DoubleEndedIterator<Item = &String> {
-    let regex = Regex::new(r":\d.\d").unwrap();
+/// The rows of the bus name list, in the order of `bus_name_state.entries`.
+pub fn name_rows(context: &BusContext) -> Vec<NameRow<'_>> {
+    if context.group_names {
+        return grouped_rows(context);
+    }
 
-    app.bus_name_state
+    context
+        .bus_name_state
         .entries
         .iter()
-        .filter(move |bus_name| app.filter_aliases && !regex.is_match(bus_name))
+        .filter(|bus_name| !context.hide_unique_names || !bus_name.starts_with(':'))
+        .map(|name| NameRow {
+            name,
+            depth: 0,
+            queued: false,
+            group: None,
+        })
+        .collect()
+}
+
+/// Each unique name followed by the names it owns or queues for, the names whose owner isn't
+/// known yet come last.
+fn grouped_rows(context: &BusContext) -> Vec<NameRow<'_>> {
+    let entries = &context.bus_name_state.entries;
+    let (connections, names): (Vec<&String>, Vec<&String>) =
+        entries.iter().partition(|name| name.starts_with(':'));
+
+    let mut owned: HashMap<&str, Vec<NameRow>> = HashMap::new();
+    let mut unowned = Vec::new();
+    for name in names {
+        let details = context
+            .name_details
+            .get(name)
+            .filter(|details| entries.contains(&details.owner));
+        let details = match details {
+            Some(details) => details,
+            None => {
+                unowned.push(NameRow {
+                    name,
+                    depth: 0,
+                    queued: false,
+                    group: None,
+                });
+                continue;
+            }
+        };
+
+        let owners = std::iter::once((&details.owner, false))
+            .chain(details.queued.iter().map(|queued| (queued, true)));
+        for (owner, queued) in owners {
+            owned.entry(owner).or_default().push(NameRow {
+                name,
+                depth: 1,
+                queued,
+                group: Some(owner),
+            });
+        }
+    }
+
+    let mut rows = Vec::new();
+    for connection in connections {
+        rows.push(NameRow {
+            name: connection,
+            depth: 0,
+            queued: false,
+            group: None,
+        });
+        rows.extend(owned.remove(connection.as_str()).unwrap_or_default());
+    }
+    rows.extend(unowned);
+
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        app::{App, Section},
+        dbus::NameDetails,
+    };
+
+    use super::{filter_bus_names, name_rows, visible_rows, NameRow, Search, SearchMode};
+
+    /// The matched ranges as `(start, end)`.
+    fn find(mode: SearchMode, query: &str, text: &str) -> Option<Vec<(usize, usize)>> {
+        let search = Search {
+            query: query.to_string(),
+            mode,
+            editing: false,
+        };
+        let ranges = search.matcher().unwrap().unwrap().find(text)?;
+        Some(
+            ranges
+                .iter()
+                .map(|range| (range.start, range.end))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_matchers() {
+        assert_eq!(
+            find(SearchMode::Substring, "dbus", "org.freedesktop.DBus"),
+            Some(vec![(16, 20)])
+        );
+        // upper case in the query makes it case sensitive
+        assert_eq!(
+            find(SearchMode::Substring, "Dbus", "org.freedesktop.DBus"),
+            None
+        );
+        assert_eq!(
+            find(SearchMode::Fuzzy, "ofdb", "org.freedesktop.DBus"),
+            Some(vec![(0, 1), (4, 5), (8, 9), (17, 18)])
+        );
+        assert_eq!(find(SearchMode::Fuzzy, "bo", "org.freedesktop.DBus"), None);
+        assert_eq!(
+            find(SearchMode::Regex, r"^:\d+\.\d+$", ":1.42"),
+            Some(vec![(0, 5)])
+        );
+        assert_eq!(
+            find(SearchMode::Substring, "é", "Zoë café"),
+            Some(vec![(8, 10)])
+        );
+
+        let invalid = Search {
+            query: String::from("org.("),
+            mode: SearchMode::Regex,
+            editing: true,
+        };
+        assert!(invalid.matcher().is_err());
+        assert!(Search::default().matcher().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_search_keeps_ancestors() {
+        let mut app = App::default();
+        let context = &mut app.contexts[0];
+        context.paths.entries = ["/", "/org", "/org/a", "/org/b", "/net"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            visible_rows(context, &Section::BusPath),
+            vec![0, 1, 2, 3, 4]
+        );
+
+        context.searches.insert(
+            Section::BusPath,
+            Search {
+                query: String::from("org/a"),
+                ..Search::default()
+            },
+        );
+        assert_eq!(visible_rows(context, &Section::BusPath), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let mut app = App::default();
+        let context = &mut app.contexts[0];
+        context.bus_name_state.entries = [":1.1", ":1.10", "org.a"].map(String::from).to_vec();
+
+        let names: Vec<&String> = filter_bus_names(context).collect();
+        assert_eq!(names, vec!["org.a"]);
+
+        context.hide_unique_names = false;
+        let names: Vec<&String> = filter_bus_names(context).collect();
+        assert_eq!(names, vec![":1.1", ":1.10", "org.a"]);
+    }
+
+    #[test]
+    fn test_group_names() {
+        let mut app = App::default();
+        let context = &mut app.contexts[0];
+        context.bus_name_state.entries = [":1.1", ":1.2", "org.a", "org.b", "org.c"]
+            .map(String::from)
+            .to_vec();
+        let owned_by = |owner: &str, queued: &[&str]| NameDetails {
+            owner: owner.to_string(),
+            queued: queued.iter().map(|queued| queued.to_string()).collect(),
+            ..NameDetails::default()
+        };
+        context
+            .name_details
+            .insert(String::from("org.a"), owned_by(":1.1", &[":1.2"]));
+        context
+            .name_details
+            .insert(String::from("org.b"), owned_by(":1.1", &[]));
+
+        let names: Vec<&String> = filter_bus_names(context).collect();
+        assert_eq!(names, vec!["org.a", "org.b", "org.c"]);
+
+        context.group_names = true;
+        let names: Vec<&String> = filter_bus_names(context).collect();
+        assert_eq!(
+            names,
+            vec![":1.1", "org.a", "org.b", ":1.2", "org.a", "org.c"]
+        );
+        assert_eq!(
+            name_rows(context)[4],
+            NameRow {
+                name: &String::from("org.a"),
+                depth: 1,
+                queued: true,
+                group: Some(&String::from(":1.2")),
+            }
+        );
+    }
 }
@@ -0,0 +1,149 @@
+use byteorder::LE;
+use std::{
+    convert::TryFrom,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use zbus::Message;
+use zvariant::{Array, EncodingContext, Signature, Structure, Value};
+
+use crate::error::DBusConsoleResult;
+
+/// Renders the body of `message` in the same syntax arguments are typed in.
+pub fn format_body(message: &Message) -> DBusConsoleResult<String> {
+    match message.body_signature() {
+        Ok(signature) if !signature.is_empty() => {
+            Ok(format_structure(&message.body::<Structure>()?))
+        }
+        _ => Ok(String::from("()")),
+    }
+}
+
+pub fn format_structure(structure: &Structure) -> String {
+    format!("({})", format_values(structure.fields()))
+}
+
+pub fn format_value(value: &Value) -> String {
+    match value {
+        Value::U8(v) => v.to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::I16(v) => v.to_string(),
+        Value::U16(v) => v.to_string(),
+        Value::I32(v) => v.to_string(),
+        Value::U32(v) => v.to_string(),
+        Value::I64(v) => v.to_string(),
+        Value::U64(v) => v.to_string(),
+        Value::F64(v) => format!("{:?}", v),
+        Value::Str(v) => format!("{:?}", v.as_str()),
+        Value::Signature(v) => format!("{:?}", v.as_str()),
+        Value::ObjectPath(v) => format!("{:?}", v.as_str()),
+        Value::Value(v) => format_value(v),
+        Value::Array(v) => format!("[{}]", format_values(v.get())),
+        Value::Dict(v) => format_dict(v),
+        Value::Structure(v) => format_structure(v),
+        Value::Fd(v) => v.to_string(),
+        #[allow(unreachable_patterns)]
+        value => format!("{:?}", value),
+    }
+}
+
+fn format_values(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(format_value)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Renders the UTC time of day of `time` as `HH:MM:SS.mmm`.
+pub fn format_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() % 86400;
+
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// zvariant doesn't expose the entries of a `Dict`, but `a{kv}` is encoded exactly like
+/// `a(kv)`, so the dict is re-read as an array of key value structures.
+fn format_dict(dict: &zvariant::Dict) -> String {
+    let ctxt = EncodingContext::<LE>::new_dbus(0);
+    let entries_signature = dict
+        .full_signature()
+        .as_str()
+        .replace('{', "(")
+        .replace('}', ")");
+
+    let entries =
+        zvariant::to_bytes_for_signature(ctxt, dict.full_signature(), dict).and_then(|bytes| {
+            let signature = Signature::try_from(entries_signature)?;
+            zvariant::from_slice_for_dynamic_signature::<_, Array>(&bytes, ctxt, &signature).map(
+                |array| {
+                    array
+                        .get()
+                        .iter()
+                        .filter_map(|entry| match entry {
+                            Value::Structure(entry) => match entry.fields() {
+                                [key, value] => {
+                                    Some(format!("{}: {}", format_value(key), format_value(value)))
+                                }
+                                _ => None,
+                            },
+                            _ => None,
+                        })
+                        .collect::<Vec<String>>()
+                },
+            )
+        });
+
+    match entries {
+        Ok(entries) => format!("{{{}}}", entries.join(", ")),
+        Err(e) => format!("<{}>", e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use zvariant::{Dict, StructureBuilder, Value};
+
+    use super::{format_structure, format_time, format_value};
+
+    #[test]
+    fn test_format_structure() {
+        let structure = StructureBuilder::new()
+            .add_field("str")
+            .add_field(1u32)
+            .add_field(vec![1i32, 2])
+            .build();
+
+        assert_eq!(format_structure(&structure), "(\"str\", 1, [1, 2])");
+    }
+
+    #[test]
+    fn test_format_dict() {
+        let mut map = HashMap::new();
+        map.insert("k", Value::new("v"));
+
+        assert_eq!(
+            format_value(&Value::Dict(Dict::from(map))),
+            "{\"k\": \"v\"}"
+        );
+    }
+
+    #[test]
+    fn test_format_time() {
+        let time = UNIX_EPOCH + Duration::from_millis(86400 * 1000 + 3723 * 1000 + 45);
+
+        assert_eq!(format_time(time), "01:02:03.045");
+    }
+}
@@ -1,13 +1,37 @@
+use bus::{BusAddress, USAGE};
+use dbus::Discovery;
 use ui::run_ui;
 
 mod app;
+mod arguments;
+mod bus;
 mod dbus;
 mod error;
 mod filter;
+mod format;
+mod monitor;
+mod parser;
+mod signals;
 mod tokenizer;
 mod ui;
 mod widgets;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    run_ui()
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let parsed = Discovery::from_args(args)
+        .and_then(|(discovery, args)| Ok((BusAddress::from_args(args)?, discovery)));
+    let (bus, discovery) = match parsed {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    run_ui(bus, discovery)
 }
@@ -0,0 +1,233 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant, SystemTime},
+};
+
+use futures::{
+    future::{AbortHandle, Abortable},
+    StreamExt,
+};
+use zbus::{Message, MessageFlags, MessageStream, MessageType, Proxy};
+
+use crate::{
+    app::{AppEvent, EventSender},
+    bus::BusAddress,
+    error::DBusConsoleResult,
+    format::format_body,
+};
+
+/// Calls without a reply are forgotten after the default D-Bus method call timeout.
+const CALL_TIMEOUT: Duration = Duration::from_secs(25);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitoredMessage {
+    pub received: SystemTime,
+    /// One of `call`, `return`, `error` or `signal`.
+    pub message_type: &'static str,
+    pub serial: u32,
+    pub reply_serial: Option<u32>,
+    pub sender: String,
+    pub destination: String,
+    /// For replies these are taken from the call being replied to.
+    pub path: String,
+    pub interface: String,
+    pub member: String,
+    pub error_name: Option<String>,
+    pub signature: String,
+    pub body: String,
+    /// Time from the call to this reply, if the call was seen.
+    pub latency: Option<Duration>,
+}
+
+impl MonitoredMessage {
+    /// The error name of errors, the path and member of anything else.
+    pub fn member_label(&self) -> String {
+        match &self.error_name {
+            Some(error_name) => error_name.to_owned(),
+            None => format!("{} {}.{}", self.path, self.interface, self.member),
+        }
+    }
+}
+
+struct PendingCall {
+    sent: Instant,
+    path: String,
+    interface: String,
+    member: String,
+}
+
+/// Eavesdrops on all traffic of the bus and forwards every message to `events` until the
+/// returned handle is aborted. This uses a connection of its own since a monitor connection
+/// can't be used for anything else, it's closed once aborted.
+pub async fn start_bus_monitor(
+    address: &BusAddress,
+    events: EventSender,
+) -> DBusConsoleResult<AbortHandle> {
+    let con = address.connect().await?;
+    let mut messages = MessageStream::from(&con);
+    // zbus' `MonitoringProxy` addresses a non-existent `org.freedesktop.DBus.Monitoring` service
+    Proxy::new(
+        &con,
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        "org.freedesktop.DBus.Monitoring",
+    )
+    .await?
+    .call_method("BecomeMonitor", &(Vec::<&str>::new(), 0u32))
+    .await?;
+
+    let (abort, registration) = AbortHandle::new_pair();
+    async_std::task::spawn(Abortable::new(
+        async move {
+            // keyed by the unique name of the caller and the serial of the call
+            let mut pending_calls: HashMap<(String, u32), PendingCall> = HashMap::new();
+
+            while let Some(message) = messages.next().await {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(_) => continue,
+                };
+                let message = to_monitored_message(&message, &mut pending_calls);
+                if !events.send(AppEvent::MessageMonitored(message)) {
+                    break;
+                }
+            }
+        },
+        registration,
+    ));
+
+    Ok(abort)
+}
+
+fn to_monitored_message(
+    message: &Message,
+    pending_calls: &mut HashMap<(String, u32), PendingCall>,
+) -> MonitoredMessage {
+    let header = message.header().ok();
+
+    let mut monitored = MonitoredMessage {
+        received: SystemTime::now(),
+        message_type: match message.message_type() {
+            MessageType::MethodCall => "call",
+            MessageType::MethodReturn => "return",
+            MessageType::Error => "error",
+            MessageType::Signal => "signal",
+            MessageType::Invalid => "invalid",
+        },
+        serial: message
+            .primary_header()
+            .serial_num()
+            .copied()
+            .unwrap_or_default(),
+        reply_serial: message.reply_serial(),
+        sender: header
+            .as_ref()
+            .and_then(|header| header.sender().ok()?.map(|name| name.to_string()))
+            .unwrap_or_default(),
+        destination: header
+            .as_ref()
+            .and_then(|header| header.destination().ok()?.map(|name| name.to_string()))
+            .unwrap_or_default(),
+        path: message
+            .path()
+            .map(|path| path.to_string())
+            .unwrap_or_default(),
+        interface: message
+            .interface()
+            .map(|interface| interface.to_string())
+            .unwrap_or_default(),
+        member: message
+            .member()
+            .map(|member| member.to_string())
+            .unwrap_or_default(),
+        error_name: header
+            .as_ref()
+            .and_then(|header| header.error_name().ok()?.map(|name| name.to_string())),
+        signature: message
+            .body_signature()
+            .map(|signature| signature.to_string())
+            .unwrap_or_default(),
+        body: format_body(message).unwrap_or_else(|e| format!("<{:?}>", e)),
+        latency: None,
+    };
+
+    match message.message_type() {
+        MessageType::MethodCall
+            if !message
+                .primary_header()
+                .flags()
+                .contains(MessageFlags::NoReplyExpected) =>
+        {
+            let now = Instant::now();
+            pending_calls.retain(|_, call| now.duration_since(call.sent) < CALL_TIMEOUT);
+            pending_calls.insert(
+                (monitored.sender.to_owned(), monitored.serial),
+                PendingCall {
+                    sent: now,
+                    path: monitored.path.to_owned(),
+                    interface: monitored.interface.to_owned(),
+                    member: monitored.member.to_owned(),
+                },
+            );
+        }
+        MessageType::MethodReturn | MessageType::Error => {
+            let call = monitored.reply_serial.and_then(|reply_serial| {
+                pending_calls.remove(&(monitored.destination.to_owned(), reply_serial))
+            });
+            if let Some(call) = call {
+                monitored.latency = Some(call.sent.elapsed());
+                monitored.path = call.path;
+                monitored.interface = call.interface;
+                monitored.member = call.member;
+            }
+        }
+        _ => {}
+    }
+
+    monitored
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use futures::{channel::mpsc, StreamExt};
+
+    use crate::{
+        app::{AppEvent, EventSender},
+        bus::BusAddress,
+        dbus::DBusClient,
+    };
+
+    use super::start_bus_monitor;
+
+    #[async_std::test]
+    async fn test_correlate_call_and_return() {
+        let (sender, events) = mpsc::unbounded();
+        let monitor = start_bus_monitor(&BusAddress::Session, EventSender::new(0, sender))
+            .await
+            .unwrap();
+
+        let client = DBusClient::new(BusAddress::Session.connect().await.unwrap());
+        client.list_names().await.unwrap();
+
+        let mut replies = events
+            .take(100)
+            .filter_map(|(_, event)| async move {
+                match event {
+                    AppEvent::MessageMonitored(message)
+                        if message.message_type == "return" && message.member == "ListNames" =>
+                    {
+                        Some(message)
+                    }
+                    _ => None,
+                }
+            })
+            .boxed();
+        let reply = replies.next().await.unwrap();
+
+        assert_eq!(reply.interface, "org.freedesktop.DBus");
+        assert!(reply.latency.unwrap() < Duration::from_secs(5));
+        monitor.abort();
+    }
+}
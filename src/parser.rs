@@ -0,0 +1,337 @@
+use std::{borrow::Cow, fmt::Display};
+
+use crate::tokenizer::{unescape, Span, Token, TokenStream, TokenType};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Expr<'a> {
+    pub span: Span,
+    pub kind: ExprKind<'a>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExprKind<'a> {
+    Struct(Vec<Expr<'a>>),
+    Array(Vec<Expr<'a>>),
+    Dict(Vec<(Expr<'a>, Expr<'a>)>),
+    /// Content of a string literal without the surrounding quotes and escapes resolved.
+    String(Cow<'a, str>),
+    Number(&'a str),
+    Boolean(bool),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParserError {
+    pub kind: ParserErrorKind,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParserErrorKind {
+    /// An opening bracket is never closed, the span points at the opening bracket.
+    UnclosedBracket,
+    /// A closing bracket doesn't match the innermost opening bracket.
+    MismatchedBracket,
+    /// A closing bracket without any opening bracket.
+    UnexpectedClosingBracket,
+    /// Two values follow each other without a `,` in between.
+    MissingSeparator,
+    /// A `,` or `:` where a value was expected.
+    MissingValue,
+    /// A `:` outside of a dict entry.
+    StrayAssignment,
+    /// A dict key not followed by `:`.
+    MissingAssignment,
+    UnexpectedEndOfInput,
+    /// Tokens following the complete top level value.
+    TrailingInput,
+}
+
+impl Display for ParserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self.kind {
+            ParserErrorKind::UnclosedBracket => "Unclosed bracket",
+            ParserErrorKind::MismatchedBracket => "Mismatched closing bracket",
+            ParserErrorKind::UnexpectedClosingBracket => "Unexpected closing bracket",
+            ParserErrorKind::MissingSeparator => "Missing \",\" before value",
+            ParserErrorKind::MissingValue => "Expected a value",
+            ParserErrorKind::StrayAssignment => "\":\" outside of a dict entry",
+            ParserErrorKind::MissingAssignment => "Missing \":\" after dict key",
+            ParserErrorKind::UnexpectedEndOfInput => "Unexpected end of input",
+            ParserErrorKind::TrailingInput => "Unexpected input after value",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+/// Parses a complete value, e.g. `("str", 1, [1, 2], {"k": "v"})`, from `token_stream`.
+pub fn parse<'a>(token_stream: TokenStream<'a, TokenType>) -> Result<Expr<'a>, ParserError> {
+    let mut parser = Parser {
+        tokens: token_stream
+            .tokens
+            .into_iter()
+            .filter(|token| token.token_type != TokenType::Whitespace)
+            .collect(),
+        position: 0,
+    };
+
+    let expr = parser.expr()?;
+
+    match parser.peek() {
+        Some(token) => Err(parser.error(ParserErrorKind::TrailingInput, token)),
+        None => Ok(expr),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a, TokenType>>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn expr(&mut self) -> Result<Expr<'a>, ParserError> {
+        let (token_type, content, span) = match self.next() {
+            Some(token) => (token.token_type.clone(), token.content, token.span.clone()),
+            None => return Err(self.end_of_input()),
+        };
+
+        match token_type {
+            TokenType::String => Ok(Expr {
+                kind: ExprKind::String(unescape(content)),
+                span,
+            }),
+            TokenType::Boolean => Ok(Expr {
+                kind: ExprKind::Boolean(content == "true"),
+                span,
+            }),
+            TokenType::Number => Ok(Expr {
+                kind: ExprKind::Number(content),
+                span,
+            }),
+            TokenType::StructStart => {
+                let (elements, end) = self.elements(&span, TokenType::StructEnd)?;
+                Ok(Expr {
+                    kind: ExprKind::Struct(elements),
+                    span: join(&span, &end),
+                })
+            }
+            TokenType::ArrayStart => {
+                let (elements, end) = self.elements(&span, TokenType::ArrayEnd)?;
+                Ok(Expr {
+                    kind: ExprKind::Array(elements),
+                    span: join(&span, &end),
+                })
+            }
+            TokenType::DictStart => self.dict(span),
+            TokenType::StructEnd | TokenType::ArrayEnd | TokenType::DictEnd => Err(ParserError {
+                kind: ParserErrorKind::UnexpectedClosingBracket,
+                span,
+            }),
+            TokenType::DictAssignmentOperator => Err(ParserError {
+                kind: ParserErrorKind::StrayAssignment,
+                span,
+            }),
+            TokenType::Seperator | TokenType::Whitespace => Err(ParserError {
+                kind: ParserErrorKind::MissingValue,
+                span,
+            }),
+        }
+    }
+
+    /// Parses `,` separated values up to and including `end`, returning the span of `end`.
+    fn elements(
+        &mut self,
+        start: &Span,
+        end: TokenType,
+    ) -> Result<(Vec<Expr<'a>>, Span), ParserError> {
+        let mut elements = Vec::new();
+
+        loop {
+            if let Some(end_span) = self.closing(start, &end)? {
+                return Ok((elements, end_span));
+            }
+            if !elements.is_empty() {
+                self.separator()?;
+            }
+            elements.push(self.expr()?);
+        }
+    }
+
+    fn dict(&mut self, start: Span) -> Result<Expr<'a>, ParserError> {
+        let mut entries = Vec::new();
+
+        loop {
+            if let Some(end_span) = self.closing(&start, &TokenType::DictEnd)? {
+                return Ok(Expr {
+                    kind: ExprKind::Dict(entries),
+                    span: join(&start, &end_span),
+                });
+            }
+            if !entries.is_empty() {
+                self.separator()?;
+            }
+            let key = self.expr()?;
+            match self.next() {
+                Some(token) if token.token_type == TokenType::DictAssignmentOperator => {}
+                Some(token) => {
+                    let span = token.span.clone();
+                    return Err(ParserError {
+                        kind: ParserErrorKind::MissingAssignment,
+                        span,
+                    });
+                }
+                None => return Err(self.unclosed(&start)),
+            }
+            entries.push((key, self.expr()?));
+        }
+    }
+
+    /// Consumes the closing bracket `end` if it's next, failing on any other closing bracket
+    /// or the end of input.
+    fn closing(&mut self, start: &Span, end: &TokenType) -> Result<Option<Span>, ParserError> {
+        match self.peek() {
+            Some(token) if &token.token_type == end => {
+                let span = token.span.clone();
+                self.position += 1;
+                Ok(Some(span))
+            }
+            Some(token) if is_closing(&token.token_type) => {
+                Err(self.error(ParserErrorKind::MismatchedBracket, token))
+            }
+            Some(_) => Ok(None),
+            None => Err(self.unclosed(start)),
+        }
+    }
+
+    fn separator(&mut self) -> Result<(), ParserError> {
+        match self.peek() {
+            Some(token) if token.token_type == TokenType::Seperator => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(token) if token.token_type == TokenType::DictAssignmentOperator => {
+                Err(self.error(ParserErrorKind::StrayAssignment, token))
+            }
+            Some(token) => Err(self.error(ParserErrorKind::MissingSeparator, token)),
+            None => Err(self.end_of_input()),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token<'a, TokenType>> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token<'a, TokenType>> {
+        self.position += 1;
+        self.tokens.get(self.position - 1)
+    }
+
+    fn error(&self, kind: ParserErrorKind, token: &Token<'a, TokenType>) -> ParserError {
+        ParserError {
+            kind,
+            span: token.span.clone(),
+        }
+    }
+
+    fn unclosed(&self, start: &Span) -> ParserError {
+        ParserError {
+            kind: ParserErrorKind::UnclosedBracket,
+            span: start.clone(),
+        }
+    }
+
+    fn end_of_input(&self) -> ParserError {
+        let end = self.tokens.last().map(|token| token.span.end).unwrap_or(0);
+        ParserError {
+            kind: ParserErrorKind::UnexpectedEndOfInput,
+            span: Span { start: end, end },
+        }
+    }
+}
+
+fn is_closing(token_type: &TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::StructEnd | TokenType::ArrayEnd | TokenType::DictEnd
+    )
+}
+
+fn join(start: &Span, end: &Span) -> Span {
+    Span {
+        start: start.start,
+        end: end.end,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tokenizer::{Span, Tokenizer};
+
+    use super::{parse, Expr, ExprKind, ParserError, ParserErrorKind};
+
+    fn parse_str(input: &str) -> Result<Expr<'_>, ParserError> {
+        parse(Tokenizer::new().tokenize(input).unwrap())
+    }
+
+    fn error_of(input: &str) -> (ParserErrorKind, usize, usize) {
+        let error = parse_str(input).unwrap_err();
+        (error.kind, error.span.start, error.span.end)
+    }
+
+    #[test]
+    fn test_parse_complex_structure() {
+        let expr = parse_str("(\"test\", -3.1, { \"key\": \"value\" }, [ 1, 2 ])").unwrap();
+
+        assert_eq!(expr.span, Span { start: 0, end: 44 });
+        match expr.kind {
+            ExprKind::Struct(fields) => {
+                assert_eq!(fields.len(), 4);
+                assert_eq!(fields[0].kind, ExprKind::String("test".into()));
+                assert_eq!(fields[0].span, Span { start: 1, end: 7 });
+                assert_eq!(fields[1].kind, ExprKind::Number("-3.1"));
+                match &fields[2].kind {
+                    ExprKind::Dict(entries) => {
+                        assert_eq!(entries[0].0.kind, ExprKind::String("key".into()));
+                        assert_eq!(entries[0].1.kind, ExprKind::String("value".into()));
+                    }
+                    kind => panic!("Expected dict, got {:?}", kind),
+                }
+                assert_eq!(fields[2].span, Span { start: 15, end: 33 });
+                match &fields[3].kind {
+                    ExprKind::Array(elements) => assert_eq!(elements.len(), 2),
+                    kind => panic!("Expected array, got {:?}", kind),
+                }
+            }
+            kind => panic!("Expected struct, got {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn test_parse_empty_containers() {
+        assert_eq!(parse_str("()").unwrap().kind, ExprKind::Struct(vec![]));
+        assert_eq!(parse_str("[]").unwrap().kind, ExprKind::Array(vec![]));
+        assert_eq!(parse_str("{}").unwrap().kind, ExprKind::Dict(vec![]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(error_of("(1, [2"), (ParserErrorKind::UnclosedBracket, 4, 5));
+        assert_eq!(error_of("(1]"), (ParserErrorKind::MismatchedBracket, 2, 3));
+        assert_eq!(
+            error_of(")"),
+            (ParserErrorKind::UnexpectedClosingBracket, 0, 1)
+        );
+        assert_eq!(error_of("(1 2)"), (ParserErrorKind::MissingSeparator, 3, 4));
+        assert_eq!(
+            error_of("(1, : 2)"),
+            (ParserErrorKind::StrayAssignment, 4, 5)
+        );
+        assert_eq!(error_of("(1: 2)"), (ParserErrorKind::StrayAssignment, 2, 3));
+        assert_eq!(
+            error_of("{1 2}"),
+            (ParserErrorKind::MissingAssignment, 3, 4)
+        );
+        assert_eq!(error_of("(1,,2)"), (ParserErrorKind::MissingValue, 3, 4));
+        assert_eq!(error_of("(1) 2"), (ParserErrorKind::TrailingInput, 4, 5));
+        assert_eq!(error_of(""), (ParserErrorKind::UnexpectedEndOfInput, 0, 0));
+    }
+}
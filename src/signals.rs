@@ -0,0 +1,382 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use futures::{
+    future::{self, AbortHandle, Abortable},
+    stream, StreamExt,
+};
+use zbus::{fdo::DBusProxy, Connection, Message, MessageStream, MessageType};
+
+use crate::{
+    app::{AppEvent, EventSender},
+    bus::BusAddress,
+    dbus::DBusClient,
+    error::{DBusConsoleError, DBusConsoleResult},
+    format::format_body,
+    tokenizer::Span,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signal {
+    pub received: SystemTime,
+    /// Unique name of the sending connection.
+    pub sender: String,
+    /// A well-known name owned by the sender, if it owns any.
+    pub sender_name: Option<String>,
+    pub path: String,
+    pub interface: String,
+    pub member: String,
+    pub body: String,
+}
+
+impl Signal {
+    pub fn member_label(&self) -> String {
+        format!("{}.{}", self.interface, self.member)
+    }
+}
+
+/// The parts of a D-Bus match rule the signal monitor can be narrowed by.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchRule {
+    pub sender: Option<String>,
+    pub interface: Option<String>,
+    pub member: Option<String>,
+    pub path: Option<String>,
+    pub arg0: Option<String>,
+}
+
+impl MatchRule {
+    /// Parses space separated `key=value` pairs, e.g. `interface=org.foo member=Bar`.
+    pub fn parse(input: &str) -> DBusConsoleResult<MatchRule> {
+        let mut rule = MatchRule::default();
+        let mut offset = 0;
+
+        for part in input.split(' ') {
+            let span = Span {
+                start: offset,
+                end: offset + part.len(),
+            };
+            offset = span.end + 1;
+            if part.is_empty() {
+                continue;
+            }
+
+            let (key, value) = match part.split_once('=') {
+                Some((key, value)) if !value.is_empty() => (key, value),
+                _ => return Err(invalid_rule("Expected key=value", span)),
+            };
+            let field = match key {
+                "sender" => &mut rule.sender,
+                "interface" => &mut rule.interface,
+                "member" => &mut rule.member,
+                "path" => &mut rule.path,
+                "arg0" => &mut rule.arg0,
+                _ => return Err(invalid_rule(&format!("Unknown key {}", key), span)),
+            };
+            *field = Some(value.to_string());
+        }
+
+        Ok(rule)
+    }
+
+    /// The `key=value` form accepted by `parse`.
+    pub fn to_input(&self) -> String {
+        self.fields()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn fields(&self) -> impl Iterator<Item = (&'static str, &String)> {
+        [
+            ("sender", &self.sender),
+            ("interface", &self.interface),
+            ("member", &self.member),
+            ("path", &self.path),
+            ("arg0", &self.arg0),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_ref().map(|value| (key, value)))
+    }
+}
+
+/// Renders the rule as passed to `AddMatch`, e.g. `type='signal',member='Bar'`.
+impl Display for MatchRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "type='signal'")?;
+        for (key, value) in self.fields() {
+            // a quote can't be escaped inside a quoted value, it's closed and reopened instead
+            write!(f, ",{}='{}'", key, value.replace('\'', "'\\''"))?;
+        }
+        Ok(())
+    }
+}
+
+fn invalid_rule(message: &str, span: Span) -> DBusConsoleError {
+    DBusConsoleError::InvalidArguments {
+        message: message.to_string(),
+        span,
+    }
+}
+
+/// Receives signals on a connection of its own, so a busy bus doesn't hold up browsing.
+#[derive(Debug, Clone)]
+pub struct SignalMonitor {
+    con: Connection,
+    /// Shared by all clones of the monitor, the reader stops once the last one is dropped.
+    _reader: Arc<Reader>,
+}
+
+/// The tasks reading signals and keeping their sender names current.
+#[derive(Debug)]
+struct Reader(Vec<AbortHandle>);
+
+impl Drop for Reader {
+    fn drop(&mut self) {
+        self.0.iter().for_each(|task| task.abort());
+    }
+}
+
+impl SignalMonitor {
+    /// Starts forwarding the signals matching the rule set with `set_rule` to `events`, until
+    /// the monitor is dropped.
+    pub async fn start(address: &BusAddress, events: EventSender) -> DBusConsoleResult<Self> {
+        let con = address.connect().await?;
+        // sender names are resolved on another connection, calls on the monitored one would
+        // block once the unread signals fill its queue
+        let client = DBusClient::new(address.connect().await?);
+        let (names, name_watch) = SenderNames::watch(&client).await?;
+        let mut messages = MessageStream::from(&con);
+        let (abort, registration) = AbortHandle::new_pair();
+
+        async_std::task::spawn(Abortable::new(
+            async move {
+                while let Some(message) = messages.next().await {
+                    let message = match message {
+                        Ok(message) if message.message_type() == MessageType::Signal => message,
+                        _ => continue,
+                    };
+                    let signal = to_signal(&message, &names);
+                    if !events.send(AppEvent::SignalReceived(signal)) {
+                        break;
+                    }
+                }
+            },
+            registration,
+        ));
+
+        Ok(Self {
+            con,
+            _reader: Arc::new(Reader(vec![abort, name_watch])),
+        })
+    }
+
+    /// Replaces the `previous` match rule with `rule`.
+    pub async fn set_rule(
+        &self,
+        previous: Option<&MatchRule>,
+        rule: &MatchRule,
+    ) -> DBusConsoleResult<()> {
+        let proxy = DBusProxy::new(&self.con).await?;
+        proxy.add_match(&rule.to_string()).await?;
+        if let Some(previous) = previous {
+            proxy.remove_match(&previous.to_string()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn to_signal(message: &Message, names: &SenderNames) -> Signal {
+    let sender = message
+        .header()
+        .ok()
+        .and_then(|header| header.sender().ok().flatten().map(|name| name.to_string()))
+        .unwrap_or_default();
+
+    Signal {
+        received: SystemTime::now(),
+        sender_name: names.resolve(&sender),
+        sender,
+        path: message
+            .path()
+            .map(|path| path.to_string())
+            .unwrap_or_default(),
+        interface: message
+            .interface()
+            .map(|interface| interface.to_string())
+            .unwrap_or_default(),
+        member: message
+            .member()
+            .map(|member| member.to_string())
+            .unwrap_or_default(),
+        body: format_body(message).unwrap_or_else(|e| format!("<{:?}>", e)),
+    }
+}
+
+/// The owners of the well-known names on the bus, listed once and then kept current from
+/// `NameOwnerChanged`.
+#[derive(Debug, Clone, Default)]
+struct SenderNames {
+    /// The unique name owning a well-known name.
+    owners: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl SenderNames {
+    /// Lists the owners and keeps them current until the returned handle is aborted.
+    async fn watch(client: &DBusClient) -> DBusConsoleResult<(Self, AbortHandle)> {
+        // subscribed before listing, so no change between the two is missed
+        let mut changes = client.receive_name_owner_changes().await?.boxed();
+        let names = Self::default();
+        let mut owners = stream::iter(client.list_names().await?)
+            .filter(|name| future::ready(!name.starts_with(':')))
+            .map(|name| async move { (client.get_name_owner(&name).await, name) })
+            .buffer_unordered(16);
+        while let Some((owner, name)) = owners.next().await {
+            // the name vanished meanwhile, `NameOwnerChanged` tells about that
+            if let Ok(owner) = owner {
+                names.set_owner(name, Some(owner));
+            }
+        }
+
+        let (abort, registration) = AbortHandle::new_pair();
+        let watched = names.clone();
+        async_std::task::spawn(Abortable::new(
+            async move {
+                while let Some(change) = changes.next().await {
+                    if !change.name.starts_with(':') {
+                        watched.set_owner(change.name, change.new_owner);
+                    }
+                }
+            },
+            registration,
+        ));
+
+        Ok((names, abort))
+    }
+
+    fn set_owner(&self, name: String, owner: Option<String>) {
+        let mut owners = self.owners.lock().unwrap_or_else(|e| e.into_inner());
+        match owner {
+            Some(owner) => owners.insert(name, owner),
+            None => owners.remove(&name),
+        };
+    }
+
+    /// A well-known name owned by `sender`, the first in order if it owns several.
+    fn resolve(&self, sender: &str) -> Option<String> {
+        let owners = self.owners.lock().unwrap_or_else(|e| e.into_inner());
+        owners
+            .iter()
+            .filter(|(_, owner)| *owner == sender)
+            .map(|(name, _)| name)
+            .min()
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use futures::channel::mpsc;
+    use zbus::Connection;
+
+    use crate::{
+        app::EventSender, bus::BusAddress, dbus::DBusClient, error::DBusConsoleError,
+        tokenizer::Span,
+    };
+
+    use super::{MatchRule, SenderNames, SignalMonitor};
+
+    #[test]
+    fn test_parse_match_rule() {
+        let rule = MatchRule::parse("interface=org.foo  member=Bar").unwrap();
+
+        assert_eq!(rule.interface.as_deref(), Some("org.foo"));
+        assert_eq!(rule.member.as_deref(), Some("Bar"));
+        assert_eq!(
+            rule.to_string(),
+            "type='signal',interface='org.foo',member='Bar'"
+        );
+        assert_eq!(rule.to_input(), "interface=org.foo member=Bar");
+        assert_eq!(MatchRule::parse("").unwrap().to_string(), "type='signal'");
+    }
+
+    #[test]
+    fn test_match_rule_errors() {
+        assert_eq!(
+            MatchRule::parse("path=/ foo=bar"),
+            Err(DBusConsoleError::InvalidArguments {
+                message: String::from("Unknown key foo"),
+                span: Span { start: 7, end: 14 }
+            })
+        );
+        assert_eq!(
+            MatchRule::parse("member"),
+            Err(DBusConsoleError::InvalidArguments {
+                message: String::from("Expected key=value"),
+                span: Span { start: 0, end: 6 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_escape_quotes() {
+        let rule = MatchRule {
+            arg0: Some(String::from("it's")),
+            ..MatchRule::default()
+        };
+
+        assert_eq!(rule.to_string(), "type='signal',arg0='it'\\''s'");
+    }
+
+    #[async_std::test]
+    async fn test_sender_names() {
+        let service = Connection::session().await.unwrap();
+        service.request_name("org.example.Sender").await.unwrap();
+        let client = DBusClient::new(Connection::session().await.unwrap());
+        let (names, watch) = SenderNames::watch(&client).await.unwrap();
+        let sender = service.unique_name().unwrap().to_string();
+        assert_eq!(
+            names.resolve(&sender).as_deref(),
+            Some("org.example.Sender")
+        );
+
+        service.release_name("org.example.Sender").await.unwrap();
+        for _ in 0..50 {
+            if names.resolve(&sender).is_none() {
+                break;
+            }
+            async_std::task::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(names.resolve(&sender), None);
+        watch.abort();
+    }
+
+    #[async_std::test]
+    async fn test_reader_stops_on_drop() {
+        let (sender, _events) = mpsc::unbounded();
+        let monitor = SignalMonitor::start(&BusAddress::Session, EventSender::new(0, sender))
+            .await
+            .unwrap();
+        let name = monitor.con.unique_name().unwrap().to_string();
+        let client = DBusClient::new(Connection::session().await.unwrap());
+        assert!(client.list_names().await.unwrap().contains(&name));
+
+        drop(monitor);
+        let mut names = Vec::new();
+        for _ in 0..50 {
+            names = client.list_names().await.unwrap();
+            if !names.contains(&name) {
+                break;
+            }
+            async_std::task::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(!names.contains(&name));
+    }
+}
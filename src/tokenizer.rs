@@ -1,8 +1,8 @@
-use regex::Regex;
+use std::borrow::Cow;
 
 #[derive(Debug, PartialEq, Eq)]
-struct TokenStream<'a, T> {
-    tokens: Vec<Token<'a, T>>,
+pub struct TokenStream<'a, T> {
+    pub tokens: Vec<Token<'a, T>>,
 }
 
 impl<'a, T> Default for TokenStream<'a, T> {
@@ -14,6 +14,7 @@ impl<'a, T> Default for TokenStream<'a, T> {
 }
 
 impl<'a, T> TokenStream<'a, T> {
+    #[cfg(test)]
     fn new(tokens: Vec<Token<'a, T>>) -> Self {
         Self { tokens }
     }
@@ -24,15 +25,16 @@ impl<'a, T> TokenStream<'a, T> {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct Token<'a, T> {
-    span: Span,
-    content: &'a str,
-    token_type: T,
+pub struct Token<'a, T> {
+    pub span: Span,
+    pub content: &'a str,
+    pub token_type: T,
 }
 
+/// Walks over `subject` char by char, `start` marks the beginning of the current token.
 struct Cursor<'a> {
     start: usize,
-    end: usize,
+    position: usize,
     subject: &'a str,
 }
 
@@ -40,60 +42,75 @@ impl<'a> Cursor<'a> {
     fn new(subject: &'a str) -> Self {
         Self {
             start: 0,
-            end: subject.len(),
+            position: 0,
             subject,
         }
     }
 
-    fn slice(&self) -> Option<&'a str> {
-        if self.start < self.end {
-            Some(&self.subject[self.start..self.end])
-        } else {
-            None
-        }
+    fn peek(&self) -> Option<char> {
+        self.subject[self.position..].chars().next()
     }
 
-    fn step(&mut self) {
-        if self.start < self.end {
-            self.end -= 1;
-        }
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
     }
 
-    fn next(&mut self) {
-        self.start = self.end;
-        self.end = self.subject.len();
+    fn eat_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position;
+        while self.peek().map(&predicate).unwrap_or(false) {
+            self.bump();
+        }
+        &self.subject[start..self.position]
     }
-}
 
-#[derive(Default, Debug, PartialEq, Eq, Clone)]
-struct Span {
-    start: usize,
-    end: usize,
-}
+    /// Finishes the current token and starts the next one.
+    fn token<T>(&mut self, token_type: T) -> Token<'a, T> {
+        let token = Token {
+            span: Span {
+                start: self.start,
+                end: self.position,
+            },
+            content: &self.subject[self.start..self.position],
+            token_type,
+        };
+        self.start = self.position;
+        token
+    }
 
-impl<'a, T> From<(&Cursor<'a>, T)> for Token<'a, T> {
-    fn from(tuple: (&Cursor<'a>, T)) -> Self {
-        Self {
+    fn error_from(&self, start: usize, message: &str) -> TokenizerError {
+        TokenizerError {
+            message: message.to_string(),
             span: Span {
-                start: tuple.0.start,
-                end: tuple.0.end,
+                start,
+                end: self.position,
             },
-            content: tuple.0.slice().unwrap(),
-            token_type: tuple.1,
         }
     }
+
+    fn error(&self, message: &str) -> TokenizerError {
+        self.error_from(self.start, message)
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
 }
 
 #[derive(Debug)]
-struct TokenizerError<'a> {
-    message: &'a str,
-    span: Span,
+pub struct TokenizerError {
+    pub message: String,
+    pub span: Span,
 }
 
-#[derive(Debug, PartialEq)]
-enum TokenType {
+#[derive(Debug, PartialEq, Clone)]
+pub enum TokenType {
     Number,
     String,
+    Boolean,
     StructStart, // (
     StructEnd,   // )
     ArrayStart,  // [
@@ -105,88 +122,196 @@ enum TokenType {
     Whitespace,
 }
 
-struct Tokenizer {
-    number_regex: Regex,
-    string_regex: Regex,
-    struct_start_regex: Regex,
-    struct_end_regex: Regex,
-    array_start_regex: Regex,
-    array_end_regex: Regex,
-    dict_start_regex: Regex,
-    dict_end_regex: Regex,
-    dict_assignment: Regex,
-    seperator_regex: Regex,
-    whitespace_regex: Regex,
-}
+#[derive(Default)]
+pub struct Tokenizer;
 
 impl Tokenizer {
-    fn new() -> Self {
-        Self {
-            number_regex: Regex::new(r"^-?[0-9]+(\.[0-9]+)?$").unwrap(),
-            string_regex: Regex::new(r#"^(("[a-zA-Z0-9_\.]*")|('[a-zA-Z0-9_\.]*'))$"#).unwrap(),
-            struct_start_regex: Regex::new(r"^\($").unwrap(),
-            struct_end_regex: Regex::new(r"^\)$").unwrap(),
-            array_start_regex: Regex::new(r"^\[$").unwrap(),
-            array_end_regex: Regex::new(r"^\]$").unwrap(),
-            dict_start_regex: Regex::new(r"^\{$").unwrap(),
-            dict_end_regex: Regex::new(r"^\}$").unwrap(),
-            seperator_regex: Regex::new(r"^,$").unwrap(),
-            whitespace_regex: Regex::new(r"^[ ]+$").unwrap(),
-            dict_assignment: Regex::new(r"^:$").unwrap(),
-        }
+    pub fn new() -> Self {
+        Self
     }
 
-    fn tokenize<'a>(&self, sub: &'a str) -> Result<TokenStream<'a, TokenType>, TokenizerError> {
-        // TODO rework
-
+    pub fn tokenize<'a>(&self, sub: &'a str) -> Result<TokenStream<'a, TokenType>, TokenizerError> {
         let mut cursor = Cursor::new(sub);
         let mut token_stream = TokenStream::default();
 
-        while let Some(slice) = cursor.slice() {
-            match self.match_token(slice) {
-                Some(token) => {
-                    token_stream.push((&cursor, token).into());
-                    cursor.next();
+        while let Some(c) = cursor.bump() {
+            let token_type = match c {
+                '(' => TokenType::StructStart,
+                ')' => TokenType::StructEnd,
+                '[' => TokenType::ArrayStart,
+                ']' => TokenType::ArrayEnd,
+                '{' => TokenType::DictStart,
+                '}' => TokenType::DictEnd,
+                ':' => TokenType::DictAssignmentOperator,
+                ',' => TokenType::Seperator,
+                '"' | '\'' => {
+                    self.string(&mut cursor, c)?;
+                    TokenType::String
                 }
-                None => cursor.step(),
-            }
+                '-' | '0'..='9' => {
+                    self.number(&mut cursor, c)?;
+                    TokenType::Number
+                }
+                c if c.is_whitespace() => {
+                    cursor.eat_while(char::is_whitespace);
+                    TokenType::Whitespace
+                }
+                c if c.is_alphabetic() || c == '_' => {
+                    cursor.eat_while(|c| c.is_alphanumeric() || c == '_');
+                    match &sub[cursor.start..cursor.position] {
+                        "true" | "false" => TokenType::Boolean,
+                        _ => return Err(cursor.error("Unknown identifier")),
+                    }
+                }
+                _ => return Err(cursor.error("Unexpected character")),
+            };
+            token_stream.push(cursor.token(token_type));
         }
 
         Ok(token_stream)
     }
 
-    fn match_token(&self, slice: &str) -> Option<TokenType> {
-        if self.struct_start_regex.is_match(slice) {
-            Some(TokenType::StructStart)
-        } else if self.struct_end_regex.is_match(slice) {
-            Some(TokenType::StructEnd)
-        } else if self.dict_start_regex.is_match(slice) {
-            Some(TokenType::DictStart)
-        } else if self.dict_end_regex.is_match(slice) {
-            Some(TokenType::DictEnd)
-        } else if self.dict_assignment.is_match(slice) {
-            Some(TokenType::DictAssignmentOperator)
-        } else if self.array_start_regex.is_match(slice) {
-            Some(TokenType::ArrayStart)
-        } else if self.array_end_regex.is_match(slice) {
-            Some(TokenType::ArrayEnd)
-        } else if self.string_regex.is_match(slice) {
-            Some(TokenType::String)
-        } else if self.number_regex.is_match(slice) {
-            Some(TokenType::Number)
-        } else if self.seperator_regex.is_match(slice) {
-            Some(TokenType::Seperator)
-        } else if self.whitespace_regex.is_match(slice) {
-            Some(TokenType::Whitespace)
+    /// Consumes the rest of a string literal opened by `quote`, validating its escapes.
+    fn string(&self, cursor: &mut Cursor, quote: char) -> Result<(), TokenizerError> {
+        loop {
+            let escape_start = cursor.position;
+            match cursor.bump() {
+                None => return Err(cursor.error("Unterminated string")),
+                Some(c) if c == quote => return Ok(()),
+                Some('\\') => match cursor.bump() {
+                    Some('n' | 't' | 'r' | '0' | '\\' | '\'' | '"') => {}
+                    Some('u') => {
+                        let opened = cursor.peek() == Some('{') && cursor.bump().is_some();
+                        let code = cursor.eat_while(|c| c.is_ascii_hexdigit());
+                        let closed =
+                            opened && cursor.peek() == Some('}') && cursor.bump().is_some();
+                        let valid = closed
+                            && code.len() <= 6
+                            && u32::from_str_radix(code, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .is_some();
+                        if !valid {
+                            return Err(cursor.error_from(escape_start, "Invalid unicode escape"));
+                        }
+                    }
+                    _ => return Err(cursor.error_from(escape_start, "Invalid escape")),
+                },
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// Consumes the rest of a number starting with `first`: decimals with an optional fraction
+    /// and exponent, or `0x` hexadecimal, `0o` octal and `0b` binary integers.
+    fn number(&self, cursor: &mut Cursor, first: char) -> Result<(), TokenizerError> {
+        let first_digit = if first == '-' {
+            cursor.bump()
         } else {
-            None
+            Some(first)
+        };
+
+        let radix = match (first_digit, cursor.peek()) {
+            (Some('0'), Some('x' | 'X')) => Some(16),
+            (Some('0'), Some('o' | 'O')) => Some(8),
+            (Some('0'), Some('b' | 'B')) => Some(2),
+            (Some('0'..='9'), _) => None,
+            _ => return Err(cursor.error("Expected digits")),
+        };
+
+        match radix {
+            Some(radix) => {
+                cursor.bump();
+                if cursor.eat_while(|c| c.is_digit(radix)).is_empty() {
+                    return Err(cursor.error("Expected digits"));
+                }
+            }
+            None => {
+                cursor.eat_while(|c| c.is_ascii_digit());
+                if cursor.peek() == Some('.') {
+                    cursor.bump();
+                    if cursor.eat_while(|c| c.is_ascii_digit()).is_empty() {
+                        return Err(cursor.error("Expected digits after \".\""));
+                    }
+                }
+                if let Some('e' | 'E') = cursor.peek() {
+                    cursor.bump();
+                    if let Some('+' | '-') = cursor.peek() {
+                        cursor.bump();
+                    }
+                    if cursor.eat_while(|c| c.is_ascii_digit()).is_empty() {
+                        return Err(cursor.error("Expected digits in exponent"));
+                    }
+                }
+            }
+        }
+
+        if cursor
+            .peek()
+            .map(|c| c.is_alphanumeric() || c == '.' || c == '_')
+            .unwrap_or(false)
+        {
+            cursor.eat_while(|c| c.is_alphanumeric() || c == '.' || c == '_');
+            return Err(cursor.error("Invalid number"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the content of a string token without quotes and with its escapes resolved.
+pub fn unescape(content: &str) -> Cow<'_, str> {
+    let inner = &content[1..content.len() - 1];
+
+    if !inner.contains('\\') {
+        return Cow::Borrowed(inner);
+    }
+
+    let mut unescaped = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('0') => unescaped.push('\0'),
+            Some('u') => {
+                let code: String = chars.by_ref().skip(1).take_while(|c| *c != '}').collect();
+                if let Some(c) = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                    unescaped.push(c);
+                }
+            }
+            Some(c) => unescaped.push(c),
+            None => {}
         }
     }
+
+    Cow::Owned(unescaped)
 }
 
 #[cfg(test)]
 mod test {
-    use super::{Token, TokenStream, TokenType, Tokenizer};
+    use super::{unescape, Span, Token, TokenStream, TokenType, Tokenizer};
+
+    fn token_types(input: &str) -> Vec<(TokenType, &str)> {
+        Tokenizer::new()
+            .tokenize(input)
+            .unwrap()
+            .tokens
+            .into_iter()
+            .map(|token| (token.token_type, token.content))
+            .collect()
+    }
+
+    fn error_of(input: &str) -> (String, Span) {
+        let error = Tokenizer::new().tokenize(input).unwrap_err();
+        (error.message, error.span)
+    }
 
     #[test]
     fn test_path() {
@@ -367,4 +492,101 @@ mod test {
             ]),
         );
     }
+
+    #[test]
+    fn test_strings() {
+        assert_eq!(
+            token_types(r#""/org/foo bar", 'ünï', "a\"b\\", "\u{1F600}\n""#),
+            vec![
+                (TokenType::String, "\"/org/foo bar\""),
+                (TokenType::Seperator, ","),
+                (TokenType::Whitespace, " "),
+                (TokenType::String, "'ünï'"),
+                (TokenType::Seperator, ","),
+                (TokenType::Whitespace, " "),
+                (TokenType::String, r#""a\"b\\""#),
+                (TokenType::Seperator, ","),
+                (TokenType::Whitespace, " "),
+                (TokenType::String, r#""\u{1F600}\n""#),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape("\"plain\""), "plain");
+        assert_eq!(unescape(r#""a\"b\\c""#), "a\"b\\c");
+        assert_eq!(unescape(r#"'\t\u{e9}\n\''"#), "\té\n'");
+    }
+
+    #[test]
+    fn test_numbers_and_booleans() {
+        assert_eq!(
+            token_types("(0x1F,-0o17,0b101,1e3,-2.5E-3,true,false)")
+                .into_iter()
+                .filter(|(token_type, _)| token_type == &TokenType::Number
+                    || token_type == &TokenType::Boolean)
+                .collect::<Vec<(TokenType, &str)>>(),
+            vec![
+                (TokenType::Number, "0x1F"),
+                (TokenType::Number, "-0o17"),
+                (TokenType::Number, "0b101"),
+                (TokenType::Number, "1e3"),
+                (TokenType::Number, "-2.5E-3"),
+                (TokenType::Boolean, "true"),
+                (TokenType::Boolean, "false"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            error_of("(1, ?)"),
+            (
+                String::from("Unexpected character"),
+                Span { start: 4, end: 5 }
+            )
+        );
+        assert_eq!(
+            error_of("(\"ü\", €)"),
+            (
+                String::from("Unexpected character"),
+                Span { start: 7, end: 10 }
+            )
+        );
+        assert_eq!(
+            error_of("(\"open"),
+            (
+                String::from("Unterminated string"),
+                Span { start: 1, end: 6 }
+            )
+        );
+        assert_eq!(
+            error_of(r#"("a\q")"#),
+            (String::from("Invalid escape"), Span { start: 3, end: 5 })
+        );
+        assert_eq!(
+            error_of(r#"("\u{110000}")"#),
+            (
+                String::from("Invalid unicode escape"),
+                Span { start: 2, end: 12 }
+            )
+        );
+        assert_eq!(
+            error_of("(nope)"),
+            (
+                String::from("Unknown identifier"),
+                Span { start: 1, end: 5 }
+            )
+        );
+        assert_eq!(
+            error_of("(1.2.3)"),
+            (String::from("Invalid number"), Span { start: 1, end: 6 })
+        );
+        assert_eq!(
+            error_of("(0x)"),
+            (String::from("Expected digits"), Span { start: 1, end: 3 })
+        );
+    }
 }
@@ -1,77 +1,152 @@
-use std::{error::Error, ops::Deref};
+use std::{
+    error::Error,
+    io,
+    ops::Range,
+    panic,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
-    app::{Action, App, Section},
-    filter::filter_bus_names,
+    app::{
+        selected_bus_name, Action, App, AppEvent, BusContext, LogEntry, MemberRow, MemberView,
+        NameChange, NameSort, Section, TrafficView,
+    },
+    bus::BusAddress,
+    dbus::{is_annotated, Arg, Discovery, MemberKind, Property, DEPRECATED, NO_REPLY},
+    error::{DBusConsoleError, DBusConsoleResult},
+    filter::{matcher, name_rows, path_depth, visible_rows, Matcher},
+    format::format_time,
+    tokenizer::Span,
     widgets::{ConsoleList, ConsoleListItem},
 };
+use async_std::task;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use regex::Regex;
+use futures::{select, FutureExt, StreamExt};
 use tui::{
     backend::{Backend, CrosstermBackend},
-    layout::{
-        Constraint,
-        Direction::{Horizontal, Vertical},
-        Layout, Rect,
-    },
-    style::{Modifier, Style},
-    widgets::{Block, Borders, Cell, List, ListItem, ListState, Row, Table},
-    Terminal,
+    layout::{Constraint, Direction::Horizontal, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span as TuiSpan, Spans},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Tabs, Wrap},
+    Frame, Terminal,
 };
 
-#[derive(Default)]
-struct GuiState {
-    bus_name_state: ListState,
-}
+/// How often the screen is redrawn while nothing happens, e.g. to animate the spinner.
+const TICK: Duration = Duration::from_millis(200);
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+pub fn run_ui(bus: BusAddress, discovery: Discovery) -> Result<(), Box<dyn Error>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+
+    // the panic message would be lost in the alternate screen, and the shell left in raw mode
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        default_hook(info);
+    }));
 
-pub fn run_ui() -> Result<(), Box<dyn Error>> {
-    enable_raw_mode().unwrap();
-    let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture).unwrap();
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend).unwrap();
+    let result = Terminal::new(backend)
+        .map_err(Box::from)
+        .and_then(|mut terminal| {
+            let mut app = App::new(discovery);
+            app.reduce(Action::Connect {
+                address: bus.to_string(),
+            });
 
-    let mut app = App::default();
-    let mut gui_state = GuiState::default();
-    app.reduce(Action::LoadBusNames);
+            task::block_on(run_loop(&mut app, &mut terminal))
+        });
 
-    loop {
-        // draw ui -> action -> app event -> state -> redraw
-        draw_ui(&app, &mut terminal).unwrap();
-        let action = wait_for_user_input(&app);
-        if let Action::Quit = action {
-            break;
-        }
-        app.reduce(action);
-    }
+    restore_terminal()?;
+    result
+}
 
-    // restore terminal
-    disable_raw_mode().unwrap();
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
     execute!(
-        terminal.backend_mut(),
+        io::stdout(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        Show
     )
-    .unwrap();
+}
+
+/// What woke up the main loop.
+// only ever one of these lives at a time, boxing the event wouldn't save anything
+#[allow(clippy::large_enum_variant)]
+enum LoopEvent {
+    Input(Option<crossterm::Result<Event>>),
+    App(Option<(usize, AppEvent)>),
+    Tick,
+}
 
-    terminal.show_cursor().unwrap();
+/// Redraws whenever a key is pressed, the terminal is resized, a background task or bus
+/// sends an event, or `TICK` passed, until the app quits.
+async fn run_loop<B: Backend>(
+    app: &mut App,
+    terminal: &mut Terminal<B>,
+) -> Result<(), Box<dyn Error>> {
+    let mut input = EventStream::new();
+
+    loop {
+        // draw ui -> action -> app event -> state -> redraw
+        draw_ui(app, terminal)?;
+
+        let next = select! {
+            event = input.next().fuse() => LoopEvent::Input(event),
+            event = app.events().next() => LoopEvent::App(event),
+            _ = task::sleep(TICK).fuse() => LoopEvent::Tick,
+        };
+        match next {
+            LoopEvent::Input(Some(Ok(event))) => match input_to_action(app, event) {
+                Action::Quit => break,
+                Action::None => {}
+                action => app.reduce(action),
+            },
+            // the terminal is gone
+            LoopEvent::Input(_) => break,
+            LoopEvent::App(Some((context, event))) => {
+                app.handle(context, event);
+                // a busy bus sends far more events than are worth a redraw each
+                app.handle_pending_events();
+            }
+            LoopEvent::App(None) | LoopEvent::Tick => {}
+        }
+    }
 
     Ok(())
 }
 
 fn draw_ui<B: Backend>(
-    state: &App,
-    // gui_state: &mut GuiState,
+    app: &App,
     terminal: &mut Terminal<B>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let state = app.context();
+
     terminal.draw(|f| {
+        let header_layout = Layout::default()
+            .constraints(
+                [
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Min(0),
+                ]
+                .as_ref(),
+            )
+            .split(f.size());
+        f.render_widget(draw_tabs(app), header_layout[0]);
+        draw_header(f, state, header_layout[1]);
+
         let root_layout = Layout::default()
             .constraints([Constraint::Percentage(66), Constraint::Percentage(33)].as_ref())
-            .split(f.size());
+            .split(header_layout[2]);
 
         let left_right_pane = Layout::default()
             .direction(Horizontal)
@@ -86,25 +161,586 @@ fn draw_ui<B: Backend>(
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
             .split(left_right_pane[1]);
 
-        f.render_widget(
-            draw_bus_names(state),
+        let visible = visible_rows(state, &Section::BusFrame);
+        render_table(
+            f,
+            draw_bus_names(state, &visible),
+            &visible_selection(&visible, state.bus_name_state.selected),
             left_pane[0],
-            // &mut gui_state.bus_name_state,
         );
-        f.render_widget(draw_methods(state), right_pane[0]);
-        f.render_widget(draw_bus_paths(state), left_pane[1]);
-        f.render_widget(draw_log(state, &root_layout[1]), root_layout[1]);
+        match state.member_view {
+            MemberView::Methods => {
+                let visible = visible_rows(state, &Section::Methods);
+                render_table(
+                    f,
+                    draw_members(state, &visible),
+                    &visible_selection(&visible, state.members.selected),
+                    right_pane[0],
+                );
+                draw_call(f, state, right_pane[1]);
+            }
+            MemberView::Properties => {
+                let visible = visible_rows(state, &Section::Properties);
+                render_table(
+                    f,
+                    draw_properties(state, &visible),
+                    &visible_selection(&visible, state.properties.selected),
+                    right_pane[0],
+                );
+                draw_property_value(f, state, right_pane[1]);
+            }
+        }
+        let visible = visible_rows(state, &Section::BusPath);
+        render_table(
+            f,
+            draw_bus_paths(state, &visible),
+            &visible_selection(&visible, state.paths.selected),
+            left_pane[1],
+        );
+        let bottom_pane = Layout::default()
+            .direction(Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(root_layout[1]);
+
+        f.render_widget(draw_log(state, &bottom_pane[0]), bottom_pane[0]);
+        match state.traffic_view {
+            TrafficView::Signals => draw_signals(f, state, bottom_pane[1]),
+            TrafficView::Monitor => draw_monitor(f, state, bottom_pane[1]),
+        }
+
+        match state.focus {
+            Section::MemberSearch => draw_member_search(f, state, header_layout[2]),
+            Section::ObjectQuery | Section::FoundObjects => {
+                draw_object_query(f, state, header_layout[2])
+            }
+            _ => {}
+        }
+        if let Some(error) = &state.error {
+            draw_error(f, error, header_layout[2]);
+        }
     })?;
 
     Ok(())
 }
 
-fn draw_log<'a>(state: &'a App, rect: &'a Rect) -> ConsoleList<'a> {
+fn draw_tabs(app: &App) -> Tabs<'_> {
+    let titles = app
+        .contexts
+        .iter()
+        .enumerate()
+        .map(|(index, context)| Spans::from(format!("{} {}", index + 1, context.bus)))
+        .collect();
+
+    Tabs::new(titles).select(app.active).highlight_style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::REVERSED),
+    )
+}
+
+/// The active bus, or the bus switcher while it's focused.
+fn draw_header<B: Backend>(f: &mut Frame<B>, state: &BusContext, area: Rect) {
+    let label_style = Style::default().add_modifier(Modifier::BOLD);
+
+    let header = match (&state.focus, &state.bus_input_error) {
+        (Section::BusAddress, error) => {
+            let label = "Connect to (session, system, peer:SOCKET, ADDRESS): ";
+            f.set_cursor(
+                area.x + (label.len() + state.bus_input.chars().count()) as u16,
+                area.y,
+            );
+
+            let mut spans = vec![TuiSpan::styled(label, label_style.fg(Color::Yellow))];
+            match error {
+                Some(DBusConsoleError::InvalidArguments { span, .. }) => {
+                    spans.extend(underline_span(&state.bus_input, span).0)
+                }
+                _ => spans.push(TuiSpan::raw(state.bus_input.as_str())),
+            }
+            if let Some(error) = error {
+                spans.push(TuiSpan::styled(
+                    format!("  {}", error_message(error)),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            spans.extend(draw_loading(state));
+            Spans::from(spans)
+        }
+        (_, error) => {
+            let mut spans = vec![
+                TuiSpan::styled("Bus: ", label_style),
+                TuiSpan::raw(state.bus.to_string()),
+            ];
+            if let Some(error) = error {
+                spans.push(TuiSpan::styled(
+                    format!("  {}", error_message(error)),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            spans.push(TuiSpan::styled(
+                "  (c to switch)",
+                Style::default().fg(Color::Gray),
+            ));
+            spans.extend(draw_loading(state));
+            Spans::from(spans)
+        }
+    };
+
+    f.render_widget(Paragraph::new(header), area);
+}
+
+/// A spinner with what's loading in the background, nothing if no task is running.
+fn draw_loading(state: &BusContext) -> Option<TuiSpan<'static>> {
+    if state.tasks.is_empty() {
+        return None;
+    }
+
+    let tick = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+        / TICK.as_millis();
+    let mut kinds: Vec<&str> = Vec::new();
+    for task in &state.tasks {
+        if !kinds.contains(&task.kind) {
+            kinds.push(task.kind);
+        }
+    }
+    let discovered = match state.discovered {
+        0 => String::new(),
+        count => format!(" ({} objects)", count),
+    };
+
+    Some(TuiSpan::styled(
+        format!(
+            "  {} Loading {}{} (Esc to cancel)",
+            SPINNER[tick as usize % SPINNER.len()],
+            kinds.join(", "),
+            discovered
+        ),
+        Style::default().fg(Color::Cyan),
+    ))
+}
+
+/// Pops up over the bottom of `area`, until dismissed.
+fn draw_error<B: Backend>(f: &mut Frame<B>, error: &DBusConsoleError, area: Rect) {
+    let height = area.height.min(5);
+    let popup = Rect {
+        x: area.x + area.width / 6,
+        y: area.y + area.height - height,
+        width: area.width - area.width / 3,
+        height,
+    };
+
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(explain_error(error))
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Error (Esc to dismiss)"),
+            ),
+        popup,
+    );
+}
+
+/// Where the member search and the object query are shown over the panes.
+fn popup_area(area: Rect) -> Rect {
+    Rect {
+        x: area.x + area.width / 10,
+        y: area.y + area.height / 10,
+        width: area.width - area.width / 5,
+        height: area.height - area.height / 5,
+    }
+}
+
+/// The member index over the panes, the members found so far while services are crawled.
+fn draw_member_search<B: Backend>(f: &mut Frame<B>, state: &BusContext, area: Rect) {
+    let popup = popup_area(area);
+    let visible = visible_rows(state, &Section::MemberSearch);
+    let matcher = matcher(state, &Section::MemberSearch);
+
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|&index| {
+            let member = &state.member_index.entries[index];
+            let kind = match member.kind {
+                MemberKind::Interface => "I",
+                MemberKind::Method => "M",
+                MemberKind::Signal => "S",
+                MemberKind::Property => "P",
+            };
+            let mut name = vec![TuiSpan::styled(
+                format!("{} ", kind),
+                Style::default().fg(Color::Gray),
+            )];
+            name.extend(highlight(&member.name, matcher.as_ref()));
+            Row::new([
+                Cell::from(Spans::from(name)),
+                Cell::from(member.interface.as_str()),
+                Cell::from(member.service.as_str()),
+                Cell::from(member.path.as_str()),
+            ])
+        })
+        .collect();
+
+    let title = format!(
+        "Find member ({} of {}, Enter to show)",
+        visible.len(),
+        state.member_index.entries.len()
+    );
+    let table = Table::new(rows)
+        .header(
+            Row::new(["Member", "Interface", "Service", "Path"])
+                .style(Style::default().fg(Color::Gray)),
+        )
+        .block(section_block(state, Section::MemberSearch, &title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .widths(&[
+            Constraint::Percentage(25),
+            Constraint::Percentage(30),
+            Constraint::Percentage(20),
+            Constraint::Percentage(25),
+        ]);
+
+    f.render_widget(Clear, popup);
+    render_table(
+        f,
+        table,
+        &visible_selection(&visible, state.member_index.selected),
+        popup,
+    );
+}
+
+/// The objects implementing the queried interfaces, compared by the values of their
+/// properties once they're read.
+fn draw_object_query<B: Backend>(f: &mut Frame<B>, state: &BusContext, area: Rect) {
+    let popup = popup_area(area);
+    let layout = Layout::default()
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(popup);
+
+    // each property of the queried interfaces found on any object makes a column
+    let mut columns: Vec<(&str, &str)> = Vec::new();
+    for object in &state.found_objects.entries {
+        if let Some(Ok(properties)) = &object.properties {
+            for property in properties {
+                let column = (property.interface.as_str(), property.name.as_str());
+                if !columns.contains(&column) {
+                    columns.push(column);
+                }
+            }
+        }
+    }
+
+    let rows: Vec<Row> = state
+        .found_objects
+        .entries
+        .iter()
+        .map(|object| {
+            let mut cells = vec![
+                Cell::from(object.service.as_str()),
+                Cell::from(object.path.as_str()),
+            ];
+            match &object.properties {
+                Some(Ok(properties)) => cells.extend(columns.iter().map(|&(interface, name)| {
+                    let property = properties
+                        .iter()
+                        .find(|property| property.interface == interface && property.name == name);
+                    match property {
+                        Some(property) => property_value(property),
+                        None => Cell::from(""),
+                    }
+                })),
+                Some(Err(e)) => {
+                    cells.push(Cell::from(error_message(e)).style(Style::default().fg(Color::Red)))
+                }
+                None => {}
+            }
+            Row::new(cells)
+        })
+        .collect();
+
+    let mut header = vec!["Service", "Path"];
+    header.extend(columns.iter().map(|&(_, name)| name));
+    let mut widths = vec![Constraint::Percentage(20), Constraint::Percentage(25)];
+    if !columns.is_empty() {
+        let width = (55 / columns.len()).max(1) as u16;
+        widths.extend(columns.iter().map(|_| Constraint::Percentage(width)));
+    }
+
+    let title = format!(
+        "Objects ({} in {} services, g to compare, Enter to show)",
+        state.found_objects.entries.len(),
+        state.queried_services
+    );
+    let table = Table::new(rows)
+        .header(Row::new(header).style(Style::default().fg(Color::Gray)))
+        .block(section_block(state, Section::FoundObjects, &title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .widths(&widths);
+
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(state.object_query.as_str()).block(section_block(
+            state,
+            Section::ObjectQuery,
+            "Find objects implementing (space separated interfaces, Enter to find)",
+        )),
+        layout[0],
+    );
+    if state.focus == Section::ObjectQuery {
+        f.set_cursor(
+            layout[0].x + 1 + state.object_query.chars().count() as u16,
+            layout[0].y + 1,
+        );
+    }
+    render_table(f, table, &state.found_objects.selected, layout[1]);
+}
+
+/// The value of `property`, or why it couldn't be read.
+fn property_value(property: &Property) -> Cell<'_> {
+    match (&property.value, &property.error) {
+        (Some(value), _) => Cell::from(value.as_str()),
+        (None, Some(e)) => Cell::from(format!("<unreadable: {}>", error_message(e)))
+            .style(Style::default().fg(Color::Red)),
+        (None, None) => Cell::from("<unreadable>"),
+    }
+}
+
+fn error_message(error: &DBusConsoleError) -> String {
+    match error.hint() {
+        Some(hint) => format!("{} - {}", error, hint),
+        None => error.to_string(),
+    }
+}
+
+/// The error name in bold, the rest of the error and a hint on a line of its own.
+fn explain_error(error: &DBusConsoleError) -> Vec<Spans<'static>> {
+    let style = Style::default().fg(Color::Red);
+    let message = error.to_string();
+
+    let mut lines = vec![match error.name() {
+        Some(name) => Spans::from(vec![
+            TuiSpan::styled(name.to_string(), style.add_modifier(Modifier::BOLD)),
+            TuiSpan::styled(message[name.len()..].to_string(), style),
+        ]),
+        None => Spans::from(TuiSpan::styled(message, style)),
+    }];
+    if let Some(hint) = error.hint() {
+        lines.push(Spans::from(TuiSpan::styled(
+            hint,
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+
+    lines
+}
+
+/// Renders `table` scrolled so that the selected row stays visible.
+fn render_table<B: Backend>(f: &mut Frame<B>, table: Table, selected: &Option<u32>, area: Rect) {
+    let mut table_state = TableState::default();
+    table_state.select(selected.map(|index| index as usize));
+    f.render_stateful_widget(table, area, &mut table_state);
+}
+
+/// Where the selected row of a list is among the rows its search leaves `visible`.
+fn visible_selection(visible: &[usize], selected: Option<u32>) -> Option<u32> {
+    selected
+        .and_then(|selected| visible.iter().position(|&index| index == selected as usize))
+        .map(|position| position as u32)
+}
+
+/// `text` with the parts matched by the search of its pane highlighted.
+fn highlight(text: &str, matcher: Option<&Matcher>) -> Vec<TuiSpan<'static>> {
+    let ranges = matcher
+        .and_then(|matcher| matcher.find(text))
+        .unwrap_or_default();
+    highlight_ranges(text, &ranges)
+}
+
+fn highlight_ranges(text: &str, ranges: &[Range<usize>]) -> Vec<TuiSpan<'static>> {
+    let style = Style::default()
+        .fg(Color::Magenta)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for range in ranges {
+        if range.start > start {
+            spans.push(TuiSpan::raw(text[start..range.start].to_string()));
+        }
+        spans.push(TuiSpan::styled(text[range.clone()].to_string(), style));
+        start = range.end;
+    }
+    if start < text.len() {
+        spans.push(TuiSpan::raw(text[start..].to_string()));
+    }
+    spans
+}
+
+/// The block of a pane, titled with the search of the pane if it has one.
+fn section_block(state: &BusContext, section: Section, title: &str) -> Block<'static> {
+    let title = match state.searches.get(&section) {
+        Some(search) => format!(
+            "{} /{}{} [{}{}]",
+            title,
+            search.query,
+            if search.editing { "▏" } else { "" },
+            search.mode.label(),
+            match (search.matcher(), search.editing) {
+                (Err(_), _) => ", invalid",
+                // Enter and Esc show or close the member search instead
+                (Ok(_), true) if section == Section::MemberSearch => ", ^F mode",
+                (Ok(_), true) => ", ^F mode, Enter keep, Esc clear",
+                (Ok(_), false) => "",
+            }
+        ),
+        None => title.to_string(),
+    };
+    let block = Block::default().borders(Borders::ALL).title(title);
+
+    if state.focus == section {
+        block.border_style(Style::default().fg(Color::Yellow))
+    } else {
+        block
+    }
+}
+
+fn draw_call<B: Backend>(f: &mut Frame<B>, state: &BusContext, area: Rect) {
+    let details = member_details(state);
+    let layout = Layout::default()
+        .constraints(
+            [
+                Constraint::Length((details.len() as u16 + 2).min(area.height / 2)),
+                Constraint::Length(3),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(
+        Paragraph::new(details)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("Details")),
+        layout[0],
+    );
+
+    let title = match state.selected_method() {
+        Some(method) => format!("Arguments {}({})", method.name, method.in_signature),
+        None => String::from("Arguments"),
+    };
+    let arguments = match &state.call_result {
+        Some(Err(DBusConsoleError::InvalidArguments { span, .. })) => {
+            underline_span(&state.arguments, span)
+        }
+        _ => Spans::from(state.arguments.as_str()),
+    };
+    f.render_widget(
+        Paragraph::new(arguments).block(section_block(state, Section::Arguments, &title)),
+        layout[1],
+    );
+
+    if let Section::Arguments = state.focus {
+        f.set_cursor(
+            layout[1].x + 1 + state.arguments.chars().count() as u16,
+            layout[1].y + 1,
+        );
+    }
+
+    f.render_widget(draw_result(&state.call_result), layout[2]);
+}
+
+fn draw_property_value<B: Backend>(f: &mut Frame<B>, state: &BusContext, area: Rect) {
+    let layout = Layout::default()
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(area);
+
+    let title = match state.properties.selected_entry() {
+        Some(property) => format!("Value {}: {}", property.name, property.signature),
+        None => String::from("Value"),
+    };
+    let value = match (&state.focus, state.properties.selected_entry()) {
+        (Section::PropertyValue, _) => match &state.property_result {
+            Some(Err(DBusConsoleError::InvalidArguments { span, .. })) => {
+                underline_span(&state.property_value, span)
+            }
+            _ => Spans::from(state.property_value.as_str()),
+        },
+        (_, Some(property)) => Spans::from(property.value.as_deref().unwrap_or_default()),
+        (_, None) => Spans::default(),
+    };
+    f.render_widget(
+        Paragraph::new(value).block(section_block(state, Section::PropertyValue, &title)),
+        layout[0],
+    );
+
+    if let Section::PropertyValue = state.focus {
+        f.set_cursor(
+            layout[0].x + 1 + state.property_value.chars().count() as u16,
+            layout[0].y + 1,
+        );
+    }
+
+    f.render_widget(draw_result(&state.property_result), layout[1]);
+}
+
+fn draw_result(result: &Option<DBusConsoleResult<String>>) -> Paragraph<'_> {
+    let result = match result {
+        Some(Ok(reply)) => Paragraph::new(reply.as_str()),
+        Some(Err(DBusConsoleError::InvalidArguments { message, span })) => {
+            Paragraph::new(format!("{} at {}..{}", message, span.start, span.end))
+                .style(Style::default().fg(Color::Red))
+        }
+        Some(Err(e)) => Paragraph::new(explain_error(e)),
+        None => Paragraph::new(""),
+    };
+
+    result
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title("Result"))
+}
+
+/// Marks the part of `input` covered by `span`, an empty span marks the end of the input.
+fn underline_span<'a>(input: &'a str, span: &Span) -> Spans<'a> {
+    let error_style = Style::default()
+        .fg(Color::Red)
+        .add_modifier(Modifier::UNDERLINED);
+
+    match (input.get(..span.start), input.get(span.start..span.end)) {
+        (Some(before), Some("")) => Spans::from(vec![
+            TuiSpan::raw(before),
+            TuiSpan::styled(" ", error_style),
+        ]),
+        (Some(before), Some(marked)) => Spans::from(vec![
+            TuiSpan::raw(before),
+            TuiSpan::styled(marked, error_style),
+            TuiSpan::raw(&input[span.end..]),
+        ]),
+        _ => Spans::from(input),
+    }
+}
+
+fn draw_log<'a>(state: &'a BusContext, rect: &'a Rect) -> ConsoleList<'a> {
     let entries: Vec<ConsoleListItem> = state
         .log
         .entries
         .iter()
-        .map(|log_entry| ConsoleListItem::new(format!("{:?}", log_entry)))
+        .map(|log_entry| match log_entry {
+            LogEntry::ActionEntry(action) => ConsoleListItem::new(format!("{:?}", action)),
+            LogEntry::AppEventEntry(AppEvent::Error(e)) => {
+                ConsoleListItem::new(format!("Error: {}", e)).style(Style::default().fg(Color::Red))
+            }
+            LogEntry::AppEventEntry(AppEvent::SignalReceived(signal)) => {
+                ConsoleListItem::new(format!(
+                    "Signal {} {} {}.{} {}",
+                    signal.sender, signal.path, signal.interface, signal.member, signal.body
+                ))
+                .style(Style::default().fg(Color::Cyan))
+            }
+            LogEntry::AppEventEntry(event) => ConsoleListItem::new(format!("{:?}", event)),
+        })
         .collect();
 
     let height = rect.height - 2; // TODO magic 2 is because borders occupy one line each
@@ -120,93 +756,731 @@ fn draw_log<'a>(state: &'a App, rect: &'a Rect) -> ConsoleList<'a> {
         .block(Block::default().borders(Borders::ALL).title("Log"))
 }
 
-fn draw_bus_names(state: &App) -> List {
+fn draw_signals<B: Backend>(f: &mut Frame<B>, state: &BusContext, area: Rect) {
+    let layout = Layout::default()
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(area);
+
+    let (rule, title) = match (&state.focus, &state.match_rule_error) {
+        (Section::MatchRule, Some(DBusConsoleError::InvalidArguments { message, span })) => (
+            underline_span(&state.match_rule_input, span),
+            format!("Match rule: {}", message),
+        ),
+        (Section::MatchRule, Some(e)) => (
+            Spans::from(state.match_rule_input.as_str()),
+            format!("Match rule: {:?}", e),
+        ),
+        (Section::MatchRule, None) => (
+            Spans::from(state.match_rule_input.as_str()),
+            String::from("Match rule (sender= interface= member= path= arg0=)"),
+        ),
+        (_, _) => (
+            Spans::from(
+                state
+                    .match_rule
+                    .as_ref()
+                    .map(|rule| rule.to_string())
+                    .unwrap_or_default(),
+            ),
+            String::from("Match rule"),
+        ),
+    };
+    f.render_widget(
+        Paragraph::new(rule).block(section_block(state, Section::MatchRule, &title)),
+        layout[0],
+    );
+
+    if let Section::MatchRule = state.focus {
+        f.set_cursor(
+            layout[0].x + 1 + state.match_rule_input.chars().count() as u16,
+            layout[0].y + 1,
+        );
+    }
+
+    let visible = visible_rows(state, &Section::Signals);
+    let matcher = matcher(state, &Section::Signals);
+    let selected = visible_selection(&visible, state.signals.selected);
+    let skip = follow_skip(selected, visible.len(), layout[1]);
+    let rows = visible.iter().skip(skip).map(|&index| {
+        let signal = &state.signals.entries[index];
+        let sender = match &signal.sender_name {
+            Some(name) => format!("{} ({})", name, signal.sender),
+            None => signal.sender.to_owned(),
+        };
+        Row::new([
+            Cell::from(format_time(signal.received)),
+            Cell::from(sender),
+            Cell::from(signal.path.as_str()),
+            Cell::from(Spans::from(highlight(
+                &signal.member_label(),
+                matcher.as_ref(),
+            ))),
+            Cell::from(signal.body.as_str()),
+        ])
+    });
+
+    let table = Table::new(rows)
+        .header(
+            Row::new(["Time", "Sender", "Path", "Member", "Body"])
+                .style(Style::default().fg(Color::Gray)),
+        )
+        .block(section_block(state, Section::Signals, "Signals"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .widths(&[
+            Constraint::Length(12),
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+            Constraint::Percentage(25),
+            Constraint::Percentage(35),
+        ]);
+    render_table(f, table, &selected, layout[1]);
+}
+
+/// Without a selection a table follows its newest rows, this is how many of the first rows
+/// don't fit into `area` then.
+fn follow_skip(selected: Option<u32>, rows: usize, area: Rect) -> usize {
+    // borders and the header take up three lines
+    let visible = area.height.saturating_sub(3) as usize;
+    match selected {
+        Some(_) => 0,
+        None => rows.saturating_sub(visible),
+    }
+}
+
+fn draw_monitor<B: Backend>(f: &mut Frame<B>, state: &BusContext, area: Rect) {
+    let visible = visible_rows(state, &Section::Monitor);
+    let matcher = matcher(state, &Section::Monitor);
+    let selected = visible_selection(&visible, state.monitored_messages.selected);
+    let skip = follow_skip(selected, visible.len(), area);
+    let rows = visible.iter().skip(skip).map(|&index| {
+        let message = &state.monitored_messages.entries[index];
+        let member = highlight(&message.member_label(), matcher.as_ref());
+        let style = match message.message_type {
+            "error" => Style::default().fg(Color::Red),
+            "signal" => Style::default().fg(Color::Cyan),
+            _ => Style::default(),
+        };
+        Row::new([
+            Cell::from(format_time(message.received)),
+            Cell::from(message.message_type),
+            Cell::from(message.serial.to_string()),
+            Cell::from(
+                message
+                    .reply_serial
+                    .map(|serial| serial.to_string())
+                    .unwrap_or_default(),
+            ),
+            Cell::from(message.sender.as_str()),
+            Cell::from(message.destination.as_str()),
+            Cell::from(Spans::from(member)),
+            Cell::from(message.signature.as_str()),
+            Cell::from(
+                message
+                    .latency
+                    .map(|latency| format!("{:.3}ms", latency.as_secs_f64() * 1000.0))
+                    .unwrap_or_default(),
+            ),
+            Cell::from(message.body.as_str()),
+        ])
+        .style(style)
+    });
+
+    let title = if state.is_monitoring() {
+        "Monitor"
+    } else {
+        "Monitor (not running)"
+    };
+    let table = Table::new(rows)
+        .header(
+            Row::new([
+                "Time", "Type", "Serial", "Reply", "Sender", "Dest", "Member", "Sig", "Latency",
+                "Body",
+            ])
+            .style(Style::default().fg(Color::Gray)),
+        )
+        .block(section_block(state, Section::Monitor, title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .widths(&[
+            Constraint::Length(12),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Percentage(8),
+            Constraint::Percentage(12),
+            Constraint::Percentage(25),
+            Constraint::Length(5),
+            Constraint::Length(10),
+            Constraint::Percentage(25),
+        ]);
+    render_table(f, table, &selected, area);
+}
+
+/// The names with the process owning them, sorted by the column marked with ▾. Grouped, the
+/// names follow the connection owning them.
+fn draw_bus_names<'a>(state: &'a BusContext, visible: &[usize]) -> Table<'a> {
     let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+    let matcher = matcher(state, &Section::BusFrame);
+    let name_rows = name_rows(state);
 
-    let rows: Vec<ListItem> = filter_bus_names(state)
-        .into_iter()
-        .enumerate()
-        .skip(state.bus_name_state.skip as usize)
-        .map(|tuple| {
-            let list_entry = ListItem::new(tuple.1.as_str());
-            if let Some(index) = &state.bus_name_state.selected {
-                if index == &(tuple.0 as u32) {
-                    list_entry.style(selected_style)
-                } else {
-                    list_entry
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|&index| {
+            let row = &name_rows[index];
+            let name = row.name;
+            let style = match state.name_changes.get(name) {
+                Some((NameChange::Appeared, _)) => Style::default().fg(Color::Green),
+                Some((NameChange::OwnerChanged, _)) => Style::default().fg(Color::Yellow),
+                Some((NameChange::Vanished, _)) => Style::default()
+                    .fg(Color::Red)
+                    .add_modifier(Modifier::CROSSED_OUT),
+                None if state.inactive.contains(name) => Style::default().fg(Color::Gray),
+                None => Style::default(),
+            };
+            let suffix = match (state.inactive.contains(name), row.queued) {
+                (true, _) => " (not running)",
+                (_, true) => " (queued)",
+                _ => "",
+            };
+            let mut label = vec![TuiSpan::raw("  ".repeat(row.depth))];
+            label.extend(highlight(name, matcher.as_ref()));
+            label.push(TuiSpan::raw(suffix));
+            let details = state.name_details.get(name);
+            let optional = |value: Option<u32>| value.map(|value| value.to_string());
+
+            Row::new([
+                Cell::from(Spans::from(label)),
+                Cell::from(optional(details.and_then(|details| details.pid)).unwrap_or_default()),
+                Cell::from(optional(details.and_then(|details| details.uid)).unwrap_or_default()),
+                Cell::from(
+                    details
+                        .and_then(|details| details.cmdline.as_ref().or(details.exe.as_ref()))
+                        .cloned()
+                        .unwrap_or_default(),
+                ),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let header = [
+        (NameSort::Name, "Name"),
+        (NameSort::Pid, "PID"),
+        (NameSort::Uid, "UID"),
+        (NameSort::Command, "Command"),
+    ]
+    .map(|(sort, label)| match sort == state.name_sort {
+        true => format!("{} ▾", label),
+        false => label.to_string(),
+    });
+
+    let title = match selected_bus_name(state).and_then(|name| state.name_details.get(&name)) {
+        Some(details) => {
+            let mut title = format!("Bus Names {}", details.owner);
+            for (key, value) in &details.credentials {
+                title.push_str(&format!(" {}={}", key, value));
+            }
+            title
+        }
+        None => String::from("Bus Names"),
+    };
+
+    Table::new(rows)
+        .header(Row::new(header).style(Style::default().fg(Color::Gray)))
+        .block(section_block(state, Section::BusFrame, &title))
+        .highlight_style(selected_style)
+        .widths(&[
+            Constraint::Percentage(40),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Percentage(60),
+        ])
+}
+
+/// The interface tree, members are indented below their interface.
+fn draw_members<'a>(state: &'a BusContext, visible: &[usize]) -> Table<'a> {
+    let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+    let deprecated_style = Style::default()
+        .fg(Color::Gray)
+        .add_modifier(Modifier::CROSSED_OUT);
+    let matcher = matcher(state, &Section::Methods);
+    let labelled = |kind: &str, name: &str| {
+        let mut label = vec![TuiSpan::raw(format!("  {} ", kind))];
+        label.extend(highlight(name, matcher.as_ref()));
+        Spans::from(label)
+    };
+
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|&index| {
+            let row = &state.members.entries[index];
+            let interface = &state.interfaces[row.interface()];
+            let (name, signature, annotations) = match *row {
+                MemberRow::Interface(_) => {
+                    let toggle = if state.is_expanded(interface) {
+                        "▾ "
+                    } else {
+                        "▸ "
+                    };
+                    let mut label = vec![TuiSpan::raw(toggle)];
+                    label.extend(highlight(&interface.name, matcher.as_ref()));
+                    let row = Row::new([Cell::from(Spans::from(label))])
+                        .style(Style::default().add_modifier(Modifier::BOLD));
+                    return if is_annotated(&interface.annotations, DEPRECATED) {
+                        row.style(deprecated_style)
+                    } else {
+                        row
+                    };
+                }
+                MemberRow::Method(_, index) => {
+                    let method = &interface.methods[index];
+                    let no_reply = if is_annotated(&method.annotations, NO_REPLY) {
+                        " no reply"
+                    } else {
+                        ""
+                    };
+                    (
+                        labelled("M", &method.name),
+                        format!(
+                            "({}) -> ({}){}",
+                            method.in_signature, method.out_signature, no_reply
+                        ),
+                        &method.annotations,
+                    )
+                }
+                MemberRow::Signal(_, index) => {
+                    let signal = &interface.signals[index];
+                    (
+                        labelled("S", &signal.name),
+                        format!("({})", signature_of_args(&signal.args)),
+                        &signal.annotations,
+                    )
+                }
+                MemberRow::Property(_, index) => {
+                    let property = &interface.properties[index];
+                    (
+                        labelled("P", &property.name),
+                        format!("{} {}", property.signature, property.access),
+                        &property.annotations,
+                    )
                 }
+            };
+
+            let row = Row::new([Cell::from(name), Cell::from(signature)]);
+            if is_annotated(annotations, DEPRECATED) {
+                row.style(deprecated_style)
             } else {
-                list_entry
+                row
             }
-            // list_entry
         })
         .collect();
 
-    List::new(rows)
-        .block(Block::default().borders(Borders::ALL).title("Bus Names"))
+    Table::new(rows)
+        .block(section_block(state, Section::Methods, "Interfaces"))
         .highlight_style(selected_style)
+        .widths(&[Constraint::Percentage(50), Constraint::Percentage(50)])
 }
 
-fn draw_methods(state: &App) -> Table {
-    let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+fn signature_of_args(args: &[Arg]) -> String {
+    args.iter().map(|arg| arg.signature.as_str()).collect()
+}
 
-    let rows = state.methods.iter().map(|method| {
-        let cell = Cell::from(method.as_str());
-        let row = Row::new([cell]);
+/// Everything known about the highlighted row of the interface tree.
+fn member_details(state: &BusContext) -> Vec<Spans<'static>> {
+    let row = match state.members.selected_entry() {
+        Some(row) => *row,
+        None => return Vec::new(),
+    };
+    let interface = &state.interfaces[row.interface()];
+    let label_style = Style::default().add_modifier(Modifier::BOLD);
 
-        row
-    });
+    let (mut lines, annotations) = match row {
+        MemberRow::Interface(_) => (
+            vec![Spans::from(vec![
+                TuiSpan::styled("interface ", label_style),
+                TuiSpan::raw(format!(
+                    "{}: {} methods, {} signals, {} properties",
+                    interface.name,
+                    interface.methods.len(),
+                    interface.signals.len(),
+                    interface.properties.len()
+                )),
+            ])],
+            &interface.annotations,
+        ),
+        MemberRow::Method(_, index) => {
+            let method = &interface.methods[index];
+            let mut lines = vec![Spans::from(vec![
+                TuiSpan::styled("method ", label_style),
+                TuiSpan::raw(format!("{}.{}", interface.name, method.name)),
+            ])];
+            lines.extend(arg_lines("in", &method.in_args));
+            lines.extend(arg_lines("out", &method.out_args));
+            (lines, &method.annotations)
+        }
+        MemberRow::Signal(_, index) => {
+            let signal = &interface.signals[index];
+            let mut lines = vec![Spans::from(vec![
+                TuiSpan::styled("signal ", label_style),
+                TuiSpan::raw(format!("{}.{}", interface.name, signal.name)),
+            ])];
+            lines.extend(arg_lines("arg", &signal.args));
+            (lines, &signal.annotations)
+        }
+        MemberRow::Property(_, index) => {
+            let property = &interface.properties[index];
+            (
+                vec![Spans::from(vec![
+                    TuiSpan::styled("property ", label_style),
+                    TuiSpan::raw(format!(
+                        "{}.{}: {} ({})",
+                        interface.name, property.name, property.signature, property.access
+                    )),
+                ])],
+                &property.annotations,
+            )
+        }
+    };
+
+    lines.extend(annotations.iter().map(|annotation| {
+        Spans::from(TuiSpan::styled(
+            format!("@{} = {}", annotation.name, annotation.value),
+            Style::default().fg(Color::Yellow),
+        ))
+    }));
+
+    lines
+}
+
+fn arg_lines(label: &'static str, args: &[Arg]) -> Vec<Spans<'static>> {
+    args.iter()
+        .map(|arg| {
+            Spans::from(vec![
+                TuiSpan::styled(format!("  {:<4}", label), Style::default().fg(Color::Gray)),
+                TuiSpan::raw(format!(
+                    "{}: {}",
+                    arg.name.as_deref().unwrap_or("_"),
+                    arg.signature
+                )),
+            ])
+        })
+        .collect()
+}
+
+fn draw_properties<'a>(state: &'a BusContext, visible: &[usize]) -> Table<'a> {
+    let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+    let matcher = matcher(state, &Section::Properties);
+
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|&index| {
+            let property = &state.properties.entries[index];
+            Row::new([
+                Cell::from(Spans::from(highlight(&property.name, matcher.as_ref()))),
+                Cell::from(property.signature.as_str()),
+                Cell::from(property.access.as_str()),
+                property_value(property),
+            ])
+        })
+        .collect();
 
     Table::new(rows)
-        .block(Block::default().borders(Borders::ALL).title("Methods"))
+        .header(
+            Row::new(["Name", "Type", "Access", "Value"]).style(Style::default().fg(Color::Gray)),
+        )
+        .block(section_block(state, Section::Properties, "Properties"))
         .highlight_style(selected_style)
-        .widths(&[Constraint::Percentage(100)])
+        .widths(&[
+            Constraint::Percentage(30),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(50),
+        ])
 }
 
-fn draw_bus_paths(state: &App) -> Table {
+/// The object tree, ● marks objects implementing interfaces of their own, ○ objects only
+/// holding children.
+fn draw_bus_paths<'a>(state: &'a BusContext, visible: &[usize]) -> Table<'a> {
     let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+    let matcher = matcher(state, &Section::BusPath);
 
-    let rows = state.paths.iter().map(|bus_name| {
-        let cell = Cell::from(bus_name.as_str());
-        let row = Row::new([cell]);
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|&index| {
+            let path = &state.paths.entries[index];
+            let node = state.objects.get(path);
+            let toggle = match node {
+                Some(node) if node.children.is_empty() => " ",
+                _ if state.expanded_paths.contains(path) => "▾",
+                _ => "▸",
+            };
+            let (marker, style) = match node {
+                Some(node) if node.error.is_some() => ("!", Style::default().fg(Color::Red)),
+                Some(node) if node.has_interfaces() => ("●", Style::default()),
+                Some(_) => ("○", Style::default().fg(Color::Gray)),
+                None => ("?", Style::default().fg(Color::Gray)),
+            };
+            let name = path
+                .rsplit('/')
+                .next()
+                .filter(|name| !name.is_empty())
+                .unwrap_or("/");
+            // the search matches the whole path, only the part in the last segment is highlighted
+            let offset = path.len() - name.len();
+            let matches: Vec<Range<usize>> = matcher
+                .as_ref()
+                .and_then(|matcher| matcher.find(path))
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|range| range.end > offset)
+                .map(|range| range.start.max(offset) - offset..range.end - offset)
+                .collect();
 
-        if let Some(_index) = &state.bus_name_state.selected {
-            // if selected_bus == bus_name {
-            //     row.style(selected_style)
-            // } else {
-            //     row
-            // }
-            row
-        } else {
-            row
-        }
-    });
+            let mut label = vec![TuiSpan::raw(format!(
+                "{}{} {} ",
+                "  ".repeat(path_depth(path)),
+                toggle,
+                marker
+            ))];
+            label.extend(highlight_ranges(name, &matches));
+            Row::new([Cell::from(Spans::from(label))]).style(style)
+        })
+        .collect();
+
+    let title = match state.paths.selected_entry() {
+        Some(path) => match state.objects.get(path).and_then(|node| node.error.as_ref()) {
+            Some(error) => format!("Paths {}  {}", path, error_message(error)),
+            None => format!("Paths {}", path),
+        },
+        None => match selected_bus_name(state) {
+            Some(bus_name) if state.inactive.contains(&bus_name) => {
+                String::from("Paths (not running, a to activate)")
+            }
+            _ => String::from("Paths"),
+        },
+    };
 
     Table::new(rows)
-        .block(Block::default().borders(Borders::ALL).title("Paths"))
+        .block(section_block(state, Section::BusPath, &title))
         .highlight_style(selected_style)
         .widths(&[Constraint::Percentage(100)])
 }
 
-fn wait_for_user_input(app: &App) -> Action {
-    match crossterm::event::read() {
-        Ok(Event::Key(key)) => match app.focus {
+fn input_to_action(app: &App, event: Event) -> Action {
+    let context = app.context();
+    match event {
+        // tabs can be switched from anywhere, even while typing
+        Event::Key(key) if key.code == KeyCode::Tab => Action::NextTab,
+        Event::Key(key) if key.code == KeyCode::BackTab => Action::PreviousTab,
+        // the member search always takes the keys typed, it's closed with Esc
+        Event::Key(key) if context.focus == Section::MemberSearch => match key.code {
+            KeyCode::Up => Action::SelectLastIndexedMember,
+            KeyCode::Down => Action::SelectNextIndexedMember,
+            KeyCode::Enter => context.jump_to_selected_member(),
+            KeyCode::Esc => Action::FocusBusNames,
+            KeyCode::Backspace => Action::DeleteSearch,
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Action::CycleSearchMode
+            }
+            KeyCode::Char(c) => Action::InputSearch(c),
+            _ => Action::None,
+        },
+        Event::Key(key) if context.is_searching() => match key.code {
+            KeyCode::Enter => Action::FinishSearch,
+            KeyCode::Esc => Action::ClearSearch,
+            KeyCode::Backspace => Action::DeleteSearch,
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Action::CycleSearchMode
+            }
+            KeyCode::Char(c) => Action::InputSearch(c),
+            _ => Action::None,
+        },
+        // Esc only leaves inputs once nothing is loading and no error is shown
+        Event::Key(key) if key.code == KeyCode::Esc && !context.tasks.is_empty() => {
+            Action::CancelTasks
+        }
+        Event::Key(key) if key.code == KeyCode::Esc && context.error.is_some() => {
+            Action::DismissError
+        }
+        Event::Key(key) => match context.focus {
             Section::BusFrame => match key.code {
                 KeyCode::Up | KeyCode::Char('k') => Action::SelectLastBusName,
                 KeyCode::Down | KeyCode::Char('j') => Action::SelectNextBusName,
+                KeyCode::Char('a') => context.activate_selected_name(),
+                KeyCode::Char('S') => Action::SortBusNames,
+                KeyCode::Char('g') => Action::ToggleNameGroups,
+                KeyCode::Char('/') => Action::Search,
+                KeyCode::Char('u') => Action::ToggleUniqueNames,
                 KeyCode::Char('p') => Action::FocusPaths,
+                KeyCode::Char('m') => Action::FocusMethods,
+                KeyCode::Char('r') => Action::FocusProperties,
+                KeyCode::Char('s') => Action::FocusSignals,
+                KeyCode::Char('o') => Action::FocusMonitor,
+                KeyCode::Char('c') => Action::FocusBusAddress,
+                KeyCode::Char('F') => Action::FocusMemberSearch,
+                KeyCode::Char('I') => Action::FocusObjectQuery,
+                KeyCode::Char('R') | KeyCode::F(5) => Action::Refresh,
+                KeyCode::Char('t') => Action::NewTab,
+                KeyCode::Char('w') => Action::CloseTab,
                 KeyCode::Char('q') => Action::Quit,
                 _ => Action::None,
             },
             Section::BusPath => match key.code {
-                KeyCode::Up | KeyCode::Char('k') => Action::SelectLastBusName,
-                KeyCode::Down | KeyCode::Char('j') => Action::SelectLastBusName,
+                KeyCode::Up | KeyCode::Char('k') => Action::SelectLastPath,
+                KeyCode::Down | KeyCode::Char('j') => Action::SelectNextPath,
+                KeyCode::Right | KeyCode::Char('l') => Action::ExpandPath { recursive: false },
+                KeyCode::Char('*') => Action::ExpandPath { recursive: true },
+                KeyCode::Left | KeyCode::Char('h') => Action::CollapsePath,
+                KeyCode::Enter | KeyCode::Char(' ') => Action::TogglePath,
+                KeyCode::Char('/') => Action::Search,
+                KeyCode::Char('b') => Action::FocusBusNames,
+                KeyCode::Char('m') => Action::FocusMethods,
+                KeyCode::Char('r') => Action::FocusProperties,
+                KeyCode::Char('s') => Action::FocusSignals,
+                KeyCode::Char('o') => Action::FocusMonitor,
+                KeyCode::Char('c') => Action::FocusBusAddress,
+                KeyCode::Char('F') => Action::FocusMemberSearch,
+                KeyCode::Char('I') => Action::FocusObjectQuery,
+                KeyCode::Char('R') | KeyCode::F(5) => Action::Refresh,
+                KeyCode::Char('t') => Action::NewTab,
+                KeyCode::Char('w') => Action::CloseTab,
+                KeyCode::Char('q') => Action::Quit,
+                _ => Action::None,
+            },
+            Section::Methods => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Action::SelectLastMember,
+                KeyCode::Down | KeyCode::Char('j') => Action::SelectNextMember,
+                KeyCode::Right | KeyCode::Char('l') => Action::ExpandInterface,
+                KeyCode::Left | KeyCode::Char('h') => Action::CollapseInterface,
+                KeyCode::Char(' ') => Action::ToggleInterface,
+                KeyCode::Char('/') => Action::Search,
+                KeyCode::Enter | KeyCode::Char('a') => match context.members.selected_entry() {
+                    Some(MemberRow::Interface(_)) => Action::ToggleInterface,
+                    _ => Action::FocusArguments,
+                },
+                KeyCode::Char('b') => Action::FocusBusNames,
+                KeyCode::Char('p') => Action::FocusPaths,
+                KeyCode::Char('r') => Action::FocusProperties,
+                KeyCode::Char('s') => Action::FocusSignals,
+                KeyCode::Char('o') => Action::FocusMonitor,
+                KeyCode::Char('c') => Action::FocusBusAddress,
+                KeyCode::Char('F') => Action::FocusMemberSearch,
+                KeyCode::Char('I') => Action::FocusObjectQuery,
+                KeyCode::Char('R') | KeyCode::F(5) => Action::Refresh,
+                KeyCode::Char('t') => Action::NewTab,
+                KeyCode::Char('w') => Action::CloseTab,
+                KeyCode::Char('q') => Action::Quit,
+                _ => Action::None,
+            },
+            Section::Arguments => match key.code {
+                KeyCode::Enter => context.call_selected_method(),
+                KeyCode::Esc => Action::FocusMethods,
+                KeyCode::Backspace => Action::DeleteArgument,
+                KeyCode::Char(c) => Action::InputArgument(c),
+                _ => Action::None,
+            },
+            Section::Properties => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Action::SelectLastProperty,
+                KeyCode::Down | KeyCode::Char('j') => Action::SelectNextProperty,
+                KeyCode::Enter | KeyCode::Char('e') => Action::FocusPropertyValue,
+                KeyCode::Char('/') => Action::Search,
+                KeyCode::Char('b') => Action::FocusBusNames,
+                KeyCode::Char('p') => Action::FocusPaths,
+                KeyCode::Char('m') => Action::FocusMethods,
+                KeyCode::Char('s') => Action::FocusSignals,
+                KeyCode::Char('o') => Action::FocusMonitor,
+                KeyCode::Char('c') => Action::FocusBusAddress,
+                KeyCode::Char('F') => Action::FocusMemberSearch,
+                KeyCode::Char('I') => Action::FocusObjectQuery,
+                KeyCode::Char('R') | KeyCode::F(5) => Action::Refresh,
+                KeyCode::Char('t') => Action::NewTab,
+                KeyCode::Char('w') => Action::CloseTab,
+                KeyCode::Char('q') => Action::Quit,
+                _ => Action::None,
+            },
+            Section::PropertyValue => match key.code {
+                KeyCode::Enter => context.set_selected_property(),
+                KeyCode::Esc => Action::FocusProperties,
+                KeyCode::Backspace => Action::DeletePropertyValue,
+                KeyCode::Char(c) => Action::InputPropertyValue(c),
+                _ => Action::None,
+            },
+            Section::Signals => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Action::SelectLastSignal,
+                KeyCode::Down | KeyCode::Char('j') => Action::SelectNextSignal,
+                KeyCode::Enter | KeyCode::Char('f') => Action::FocusMatchRule,
+                KeyCode::Char('/') => Action::Search,
                 KeyCode::Char('b') => Action::FocusBusNames,
+                KeyCode::Char('p') => Action::FocusPaths,
+                KeyCode::Char('m') => Action::FocusMethods,
+                KeyCode::Char('r') => Action::FocusProperties,
+                KeyCode::Char('o') => Action::FocusMonitor,
+                KeyCode::Char('c') => Action::FocusBusAddress,
+                KeyCode::Char('F') => Action::FocusMemberSearch,
+                KeyCode::Char('I') => Action::FocusObjectQuery,
+                KeyCode::Char('R') | KeyCode::F(5) => Action::Refresh,
+                KeyCode::Char('t') => Action::NewTab,
+                KeyCode::Char('w') => Action::CloseTab,
                 KeyCode::Char('q') => Action::Quit,
                 _ => Action::None,
             },
+            Section::Monitor => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Action::SelectLastMonitoredMessage,
+                KeyCode::Down | KeyCode::Char('j') => Action::SelectNextMonitoredMessage,
+                KeyCode::Char('/') => Action::Search,
+                KeyCode::Char('b') => Action::FocusBusNames,
+                KeyCode::Char('p') => Action::FocusPaths,
+                KeyCode::Char('m') => Action::FocusMethods,
+                KeyCode::Char('r') => Action::FocusProperties,
+                KeyCode::Char('s') => Action::FocusSignals,
+                KeyCode::Char('c') => Action::FocusBusAddress,
+                KeyCode::Char('F') => Action::FocusMemberSearch,
+                KeyCode::Char('I') => Action::FocusObjectQuery,
+                KeyCode::Char('R') | KeyCode::F(5) => Action::Refresh,
+                KeyCode::Char('t') => Action::NewTab,
+                KeyCode::Char('w') => Action::CloseTab,
+                KeyCode::Char('q') => Action::Quit,
+                _ => Action::None,
+            },
+            Section::BusAddress => match key.code {
+                KeyCode::Enter => Action::Connect {
+                    address: context.bus_input.to_owned(),
+                },
+                KeyCode::Esc => Action::FocusBusNames,
+                KeyCode::Backspace => Action::DeleteBusAddress,
+                KeyCode::Char(c) => Action::InputBusAddress(c),
+                _ => Action::None,
+            },
+            Section::MemberSearch => Action::None,
+            Section::ObjectQuery => match key.code {
+                KeyCode::Enter => context.find_objects(),
+                KeyCode::Esc => Action::FocusBusNames,
+                KeyCode::Down => Action::FocusFoundObjects,
+                KeyCode::Backspace => Action::DeleteObjectQuery,
+                KeyCode::Char(c) => Action::InputObjectQuery(c),
+                _ => Action::None,
+            },
+            Section::FoundObjects => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Action::SelectLastFoundObject,
+                KeyCode::Down | KeyCode::Char('j') => Action::SelectNextFoundObject,
+                KeyCode::Enter => context.show_selected_object(),
+                KeyCode::Char('g') => Action::CompareFoundObjects,
+                KeyCode::Char('i') => Action::FocusObjectQuery,
+                KeyCode::Esc | KeyCode::Char('b') => Action::FocusBusNames,
+                KeyCode::Char('q') => Action::Quit,
+                _ => Action::None,
+            },
+            Section::MatchRule => match key.code {
+                KeyCode::Enter => Action::ApplyMatchRule {
+                    rule: context.match_rule_input.to_owned(),
+                },
+                KeyCode::Esc => Action::FocusSignals,
+                KeyCode::Backspace => Action::DeleteMatchRule,
+                KeyCode::Char(c) => Action::InputMatchRule(c),
+                _ => Action::None,
+            },
         },
-        Ok(Event::Resize(_, _)) => Action::None,
+        // the loop redraws after every event, which also resizes the screen
+        Event::Resize(_, _) => Action::None,
         _ => Action::None,
     }
 }
@@ -2,13 +2,12 @@ use tui::{
     buffer::Buffer,
     layout::Rect,
     style::Style,
-    text::{Span, Text},
+    text::Span,
     widgets::{Block, Widget},
 };
 
 #[derive(Debug, Clone)]
 pub struct ConsoleListItem<'a> {
-    selected: bool,
     style: Style,
     content: Span<'a>,
 }
@@ -19,17 +18,20 @@ impl<'a> ConsoleListItem<'a> {
         T: Into<Span<'a>>,
     {
         ConsoleListItem {
-            selected: false,
             style: Style::default(),
             content: content.into(),
         }
     }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
 }
 
 pub struct ConsoleList<'a> {
     block: Option<Block<'a>>,
     items: Vec<ConsoleListItem<'a>>,
-    selected_style: Option<Style>,
     offset: usize,
 }
 
@@ -38,7 +40,6 @@ impl<'a> ConsoleList<'a> {
         Self {
             block: None,
             items,
-            selected_style: None,
             offset: 0,
         }
     }
@@ -54,8 +55,6 @@ impl<'a> ConsoleList<'a> {
     }
 
     fn do_render(mut self, area: Rect, buf: &mut Buffer) {
-        // let skip = self.calc_skip();
-
         let list_area = match self.block.take() {
             Some(b) => {
                 let inner_area = b.inner(area);
@@ -75,6 +74,10 @@ impl<'a> ConsoleList<'a> {
             .enumerate()
             .for_each(|(pos, item)| {
                 if (list_area.y + pos as u16) < (list_area.y + list_area.height) {
+                    buf.set_style(
+                        Rect::new(list_area.x, list_area.y + pos as u16, list_area.width, 1),
+                        item.style,
+                    );
                     buf.set_span(
                         list_area.x,
                         list_area.y + pos as u16,
@@ -84,10 +87,6 @@ impl<'a> ConsoleList<'a> {
                 }
             });
     }
-
-    fn calc_skip(&self) -> i32 {
-        todo!()
-    }
 }
 
 impl<'a> Widget for ConsoleList<'a> {